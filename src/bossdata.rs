@@ -21,6 +21,49 @@ pub enum Ruleset {
     PvP,
 }
 
+impl Ruleset {
+    /// Converts the flags of a `Ruleset` statechange, where bit 0 is PvE, bit 1 WvW and bit 2 PvP.
+    pub fn from_evtc(flags: u64) -> Option<Self> {
+        if flags & 1 != 0 {
+            Some(Ruleset::PvE)
+        } else if flags & 2 != 0 {
+            Some(Ruleset::WvW)
+        } else if flags & 4 != 0 {
+            Some(Ruleset::PvP)
+        } else {
+            None
+        }
+    }
+}
+
+/// The text language of the game client that recorded a log.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, FromPrimitive)]
+pub enum Language {
+    English = 0,
+    French = 2,
+    German = 3,
+    Spanish = 4,
+    Chinese = 5,
+}
+
+impl Language {
+    pub fn from_evtc(id: u64) -> Option<Self> {
+        Self::from_u64(id)
+    }
+}
+
+/// The difficulty an encounter was played on.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Difficulty {
+    NormalMode,
+    ChallengeMode,
+    LegendaryMode,
+    /// Normal mode with the given number of Emboldened stacks, which make the fight easier.
+    Emboldened(u8),
+    /// The encounter has a challenge mode, but the log does not tell which mode was played.
+    Unknown,
+}
+
 /// The game mode in which a log was produced.
 ///
 /// Note that the distinction made here is relatively arbitrary, but hopefully still useful. In
@@ -156,6 +199,135 @@ impl BossId {
     pub fn from_header_id(id: u16) -> Self {
         Self::from_u16(id).unwrap_or(Self::Unknown)
     }
+
+    /// The max health above which the boss is in challenge mode.
+    ///
+    /// The limits are the ones Elite Insights checks and sit between the normal and challenge
+    /// mode max health, e.g. Mursaat Overseer has 22,021,440 in normal mode and about 30 million
+    /// in challenge mode. `None` for bosses whose challenge mode is not told apart by health, see
+    /// [`Encounter::has_challenge_mode`] for which encounters have one.
+    pub fn challenge_mode_health(self) -> Option<u64> {
+        use BossId as BI;
+        let health = match self {
+            BI::Mo => 25_000_000,
+            BI::Samarog => 30_000_000,
+            BI::Deimos => 40_000_000,
+            BI::Dhuum => 35_000_000,
+            BI::Nikare => 18_000_000,
+            BI::Qadim => 21_000_000,
+            BI::Adina => 23_000_000,
+            BI::Sabir => 32_000_000,
+            BI::QadimThePeerless => 48_000_000,
+            BI::Cerus => 130_000_000,
+            _ => return None,
+        };
+        Some(health)
+    }
+
+    /// The max health above which the boss is in legendary mode, like
+    /// [`BossId::challenge_mode_health`].
+    pub fn legendary_mode_health(self) -> Option<u64> {
+        match self {
+            BossId::Cerus => Some(200_000_000),
+            _ => None,
+        }
+    }
+
+    /// The encounter this boss belongs to, `None` for WvW, instance and unknown logs.
+    pub fn encounter(self) -> Option<Encounter> {
+        use BossId as BI;
+        use Encounter as E;
+        let encounter = match self {
+            BI::ValeGuardian => E::ValeGuardian,
+            BI::Gorseval => E::Gorseval,
+            BI::Sabetha => E::Sabetha,
+            BI::Slothasor => E::Slothasor,
+            BI::Berg | BI::Zane | BI::Nurella => E::BanditTrio,
+            BI::Matthias => E::Matthias,
+            BI::McLeod => E::Escort,
+            BI::KeepConstruct => E::KeepConstruct,
+            BI::TwistedCastle => E::TwistedCastle,
+            BI::Xera => E::Xera,
+            BI::Cairn => E::Cairn,
+            BI::Mo => E::MursaatOverseer,
+            BI::Samarog => E::Samarog,
+            BI::Deimos => E::Deimos,
+            BI::SoullessHorror => E::SoullessHorror,
+            BI::River => E::RiverOfSouls,
+            BI::BrokenKing => E::BrokenKing,
+            BI::SoulEater => E::SoulEater,
+            BI::EyeOfJudgement | BI::EyeOfFate => E::Eyes,
+            BI::Dhuum => E::Dhuum,
+            BI::ConjuredAmalgamate => E::ConjuredAmalgamate,
+            BI::Nikare | BI::Kenut => E::TwinLargos,
+            BI::Qadim => E::Qadim,
+            BI::Adina => E::CardinalAdina,
+            BI::Sabir => E::CardinalSabir,
+            BI::QadimThePeerless => E::QadimThePeerless,
+            BI::Greer => E::Greer,
+            BI::Decima | BI::GodsquallDecima => E::Decima,
+            BI::Ura => E::Ura,
+            BI::Mama => E::Mama,
+            BI::Siax => E::Siax,
+            BI::Ensolyss => E::Ensolyss,
+            BI::Skorvald => E::Skorvald,
+            BI::Artsariiv => E::Artsariiv,
+            BI::Arkk => E::Arkk,
+            BI::SorrowfulSpellcaster => E::Ai,
+            BI::Kanaxai => E::Kanaxai,
+            BI::CerusLonelyTower | BI::DeimosLonelyTower => E::CerusAndDeimos,
+            BI::EparchLonelyTower => E::Eparch,
+            BI::WhisperingShadowKinfall => E::WhisperingShadow,
+            BI::Icebrood => E::Icebrood,
+            BI::TheVoice | BI::TheClaw => E::VoiceAndClaw,
+            BI::Fraenir | BI::FraenirConstruct => E::Fraenir,
+            BI::Boneskinner => E::Boneskinner,
+            BI::WhisperOfJormag => E::WhisperOfJormag,
+            BI::VariniaStormsounder => E::VariniaStormsounder,
+            BI::Freezie => E::Freezie,
+            BI::CaptainMaiTrin | BI::CaptainMaiTrin2 | BI::CaptainMaiTrin3 => E::MaiTrin,
+            BI::Ankka => E::Ankka,
+            BI::MinisterLi | BI::MinisterLiCm => E::MinisterLi,
+            BI::DragonVoid1 | BI::DragonVoid2 | BI::DragonVoid3 => E::DragonVoid,
+            BI::PrototypeVermilion
+            | BI::PrototypeIndigo
+            | BI::PrototypeArsenite
+            | BI::PrototypeVermilionCm
+            | BI::PrototypeIndigoCm
+            | BI::PrototypeArseniteCm => E::OldLionsCourt,
+            BI::Dagda => E::Dagda,
+            BI::Cerus => E::Cerus,
+            BI::StandardGolem
+            | BI::MediumGolem
+            | BI::LargeGolem
+            | BI::MassiveGolem
+            | BI::AverageGolem
+            | BI::VitalGolem => E::Golem,
+            BI::Wvw | BI::Instance | BI::Unknown => return None,
+        };
+        Some(encounter)
+    }
+
+    /// The game mode of a log with this boss ID, `None` for instance and unknown logs.
+    pub fn game_mode(self) -> Option<GameMode> {
+        match self {
+            BossId::Wvw => Some(GameMode::WvW),
+            _ => self.encounter().map(Encounter::game_mode),
+        }
+    }
+
+    /// Whether the species only appears in challenge mode.
+    pub fn is_challenge_mode_species(self) -> bool {
+        use BossId as BI;
+        matches!(
+            self,
+            BI::MinisterLiCm
+                | BI::PrototypeVermilionCm
+                | BI::PrototypeIndigoCm
+                | BI::PrototypeArseniteCm
+                | BI::GodsquallDecima
+        )
+    }
 }
 
 impl Display for BossId {
@@ -242,6 +414,305 @@ impl Display for BossId {
     }
 }
 
+impl GameMode {
+    /// The ruleset that is active in this game mode.
+    pub fn ruleset(self) -> Ruleset {
+        match self {
+            GameMode::WvW => Ruleset::WvW,
+            _ => Ruleset::PvE,
+        }
+    }
+}
+
+/// The expansion (or living world season) that introduced an encounter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Expansion {
+    /// Base game content, including all fractals
+    Core,
+    HeartOfThorns,
+    PathOfFire,
+    IcebroodSaga,
+    EndOfDragons,
+    SecretsOfTheObscure,
+    JanthirWilds,
+}
+
+/// The fractal an encounter takes place in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Fractal {
+    Nightmare,
+    ShatteredObservatory,
+    SunquaPeak,
+    SilentSurf,
+    LonelyTower,
+    Kinfall,
+}
+
+impl Fractal {
+    /// The fractal scale of the challenge mode, the scale the `FractalScale` statechange reports.
+    pub fn challenge_mode_scale(self) -> u64 {
+        match self {
+            Fractal::Nightmare => 100,
+            Fractal::ShatteredObservatory => 99,
+            Fractal::SunquaPeak => 98,
+            Fractal::SilentSurf => 97,
+            Fractal::LonelyTower => 96,
+            Fractal::Kinfall => 95,
+        }
+    }
+}
+
+/// A high-level encounter, grouping the boss IDs that belong to the same fight.
+///
+/// Use [`BossId::encounter`] to get the encounter of a log.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Encounter {
+    // Wing 1
+    ValeGuardian,
+    Gorseval,
+    Sabetha,
+    // Wing 2
+    Slothasor,
+    BanditTrio,
+    Matthias,
+    // Wing 3
+    Escort,
+    KeepConstruct,
+    TwistedCastle,
+    Xera,
+    // Wing 4
+    Cairn,
+    MursaatOverseer,
+    Samarog,
+    Deimos,
+    // Wing 5
+    SoullessHorror,
+    RiverOfSouls,
+    BrokenKing,
+    SoulEater,
+    Eyes,
+    Dhuum,
+    // Wing 6
+    ConjuredAmalgamate,
+    TwinLargos,
+    Qadim,
+    // Wing 7
+    CardinalAdina,
+    CardinalSabir,
+    QadimThePeerless,
+    // Wing 8
+    Greer,
+    Decima,
+    Ura,
+    // Fractals
+    Mama,
+    Siax,
+    Ensolyss,
+    Skorvald,
+    Artsariiv,
+    Arkk,
+    Ai,
+    Kanaxai,
+    CerusAndDeimos,
+    Eparch,
+    WhisperingShadow,
+    // Strikes
+    Icebrood,
+    VoiceAndClaw,
+    Fraenir,
+    Boneskinner,
+    WhisperOfJormag,
+    VariniaStormsounder,
+    Freezie,
+    MaiTrin,
+    Ankka,
+    MinisterLi,
+    DragonVoid,
+    OldLionsCourt,
+    Dagda,
+    Cerus,
+    // Training area
+    Golem,
+}
+
+impl Encounter {
+    /// The boss IDs that make up this encounter, the main boss first.
+    pub fn bosses(self) -> &'static [BossId] {
+        use BossId as BI;
+        use Encounter as E;
+        match self {
+            E::ValeGuardian => &[BI::ValeGuardian],
+            E::Gorseval => &[BI::Gorseval],
+            E::Sabetha => &[BI::Sabetha],
+            E::Slothasor => &[BI::Slothasor],
+            E::BanditTrio => &[BI::Berg, BI::Zane, BI::Nurella],
+            E::Matthias => &[BI::Matthias],
+            E::Escort => &[BI::McLeod],
+            E::KeepConstruct => &[BI::KeepConstruct],
+            E::TwistedCastle => &[BI::TwistedCastle],
+            E::Xera => &[BI::Xera],
+            E::Cairn => &[BI::Cairn],
+            E::MursaatOverseer => &[BI::Mo],
+            E::Samarog => &[BI::Samarog],
+            E::Deimos => &[BI::Deimos],
+            E::SoullessHorror => &[BI::SoullessHorror],
+            E::RiverOfSouls => &[BI::River],
+            E::BrokenKing => &[BI::BrokenKing],
+            E::SoulEater => &[BI::SoulEater],
+            E::Eyes => &[BI::EyeOfJudgement, BI::EyeOfFate],
+            E::Dhuum => &[BI::Dhuum],
+            E::ConjuredAmalgamate => &[BI::ConjuredAmalgamate],
+            E::TwinLargos => &[BI::Nikare, BI::Kenut],
+            E::Qadim => &[BI::Qadim],
+            E::CardinalAdina => &[BI::Adina],
+            E::CardinalSabir => &[BI::Sabir],
+            E::QadimThePeerless => &[BI::QadimThePeerless],
+            E::Greer => &[BI::Greer],
+            E::Decima => &[BI::Decima, BI::GodsquallDecima],
+            E::Ura => &[BI::Ura],
+            E::Mama => &[BI::Mama],
+            E::Siax => &[BI::Siax],
+            E::Ensolyss => &[BI::Ensolyss],
+            E::Skorvald => &[BI::Skorvald],
+            E::Artsariiv => &[BI::Artsariiv],
+            E::Arkk => &[BI::Arkk],
+            E::Ai => &[BI::SorrowfulSpellcaster],
+            E::Kanaxai => &[BI::Kanaxai],
+            E::CerusAndDeimos => &[BI::CerusLonelyTower, BI::DeimosLonelyTower],
+            E::Eparch => &[BI::EparchLonelyTower],
+            E::WhisperingShadow => &[BI::WhisperingShadowKinfall],
+            E::Icebrood => &[BI::Icebrood],
+            E::VoiceAndClaw => &[BI::TheVoice, BI::TheClaw],
+            E::Fraenir => &[BI::Fraenir, BI::FraenirConstruct],
+            E::Boneskinner => &[BI::Boneskinner],
+            E::WhisperOfJormag => &[BI::WhisperOfJormag],
+            E::VariniaStormsounder => &[BI::VariniaStormsounder],
+            E::Freezie => &[BI::Freezie],
+            E::MaiTrin => &[BI::CaptainMaiTrin, BI::CaptainMaiTrin2, BI::CaptainMaiTrin3],
+            E::Ankka => &[BI::Ankka],
+            E::MinisterLi => &[BI::MinisterLi, BI::MinisterLiCm],
+            E::DragonVoid => &[BI::DragonVoid1, BI::DragonVoid2, BI::DragonVoid3],
+            E::OldLionsCourt => &[
+                BI::PrototypeVermilion,
+                BI::PrototypeIndigo,
+                BI::PrototypeArsenite,
+                BI::PrototypeVermilionCm,
+                BI::PrototypeIndigoCm,
+                BI::PrototypeArseniteCm,
+            ],
+            E::Dagda => &[BI::Dagda],
+            E::Cerus => &[BI::Cerus],
+            E::Golem => &[
+                BI::StandardGolem,
+                BI::MediumGolem,
+                BI::LargeGolem,
+                BI::MassiveGolem,
+                BI::AverageGolem,
+                BI::VitalGolem,
+            ],
+        }
+    }
+
+    pub fn game_mode(self) -> GameMode {
+        use Encounter as E;
+        if self.wing().is_some() {
+            GameMode::Raid
+        } else if self.fractal().is_some() {
+            GameMode::Fractal
+        } else if self == E::Golem {
+            GameMode::Golem
+        } else {
+            GameMode::Strike
+        }
+    }
+
+    /// The raid wing of the encounter, `None` if it is not a raid.
+    pub fn wing(self) -> Option<u8> {
+        use Encounter as E;
+        let wing = match self {
+            E::ValeGuardian | E::Gorseval | E::Sabetha => 1,
+            E::Slothasor | E::BanditTrio | E::Matthias => 2,
+            E::Escort | E::KeepConstruct | E::TwistedCastle | E::Xera => 3,
+            E::Cairn | E::MursaatOverseer | E::Samarog | E::Deimos => 4,
+            E::SoullessHorror
+            | E::RiverOfSouls
+            | E::BrokenKing
+            | E::SoulEater
+            | E::Eyes
+            | E::Dhuum => 5,
+            E::ConjuredAmalgamate | E::TwinLargos | E::Qadim => 6,
+            E::CardinalAdina | E::CardinalSabir | E::QadimThePeerless => 7,
+            E::Greer | E::Decima | E::Ura => 8,
+            _ => return None,
+        };
+        Some(wing)
+    }
+
+    /// The fractal of the encounter, `None` if it is not a fractal.
+    pub fn fractal(self) -> Option<Fractal> {
+        use Encounter as E;
+        let fractal = match self {
+            E::Mama | E::Siax | E::Ensolyss => Fractal::Nightmare,
+            E::Skorvald | E::Artsariiv | E::Arkk => Fractal::ShatteredObservatory,
+            E::Ai => Fractal::SunquaPeak,
+            E::Kanaxai => Fractal::SilentSurf,
+            E::CerusAndDeimos | E::Eparch => Fractal::LonelyTower,
+            E::WhisperingShadow => Fractal::Kinfall,
+            _ => return None,
+        };
+        Some(fractal)
+    }
+
+    /// Whether the encounter can be played in challenge mode.
+    pub fn has_challenge_mode(self) -> bool {
+        use Encounter as E;
+        match self {
+            E::Escort | E::TwistedCastle | E::Xera => false,
+            E::RiverOfSouls | E::BrokenKing | E::SoulEater | E::Eyes => false,
+            E::MaiTrin
+            | E::Ankka
+            | E::MinisterLi
+            | E::DragonVoid
+            | E::OldLionsCourt
+            | E::Dagda
+            | E::Cerus => true,
+            _ => self.wing().is_some_and(|wing| wing >= 3) || self.fractal().is_some(),
+        }
+    }
+
+    pub fn expansion(self) -> Expansion {
+        use Encounter as E;
+        match self.wing() {
+            Some(1..=5) => return Expansion::HeartOfThorns,
+            Some(6 | 7) => return Expansion::PathOfFire,
+            Some(_) => return Expansion::JanthirWilds,
+            None => {}
+        }
+        match self {
+            E::Icebrood
+            | E::VoiceAndClaw
+            | E::Fraenir
+            | E::Boneskinner
+            | E::WhisperOfJormag
+            | E::VariniaStormsounder => Expansion::IcebroodSaga,
+            E::MaiTrin | E::Ankka | E::MinisterLi | E::DragonVoid => Expansion::EndOfDragons,
+            E::Dagda | E::Cerus => Expansion::SecretsOfTheObscure,
+            _ => Expansion::Core,
+        }
+    }
+}
+
+impl Display for Encounter {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Encounter::Golem => write!(f, "Training Golem"),
+            // The boss names already use the encounter name for grouped bosses
+            _ => write!(f, "{}", self.bosses()[0]),
+        }
+    }
+}
+
 /// An in-game profession.
 ///
 /// This only contains the 9 base professions. For elite specializations, see
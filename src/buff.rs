@@ -0,0 +1,246 @@
+//! Buff definitions from the `BuffInfo` and `BuffFormula` statechanges of a log.
+use std::collections::HashMap;
+
+use crate::event::Event;
+use crate::evtc::{CbtEvent, CbtStateChange};
+
+/// How stacks of a buff combine, from `pad61` of `BuffInfo`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StackingType {
+    /// Intensity stacking, where some stacks are lost on condition
+    StackingConditionalLoss,
+    /// Duration stacking, stacks queue up one after another
+    Queue,
+    /// Duration stacking with a cap on the total duration
+    CappedDuration,
+    /// Duration stacking like regeneration, where the strongest stack is active
+    Regeneration,
+    /// Intensity stacking
+    Stacking,
+    /// A single stack that is replaced by new applications
+    Force,
+    Unknown(u8),
+}
+
+impl StackingType {
+    pub fn from_u8(raw: u8) -> Self {
+        match raw {
+            0 => StackingType::StackingConditionalLoss,
+            1 => StackingType::Queue,
+            2 => StackingType::CappedDuration,
+            3 => StackingType::Regeneration,
+            4 => StackingType::Stacking,
+            5 => StackingType::Force,
+            _ => StackingType::Unknown(raw),
+        }
+    }
+
+    pub fn as_u8(self) -> u8 {
+        match self {
+            StackingType::StackingConditionalLoss => 0,
+            StackingType::Queue => 1,
+            StackingType::CappedDuration => 2,
+            StackingType::Regeneration => 3,
+            StackingType::Stacking => 4,
+            StackingType::Force => 5,
+            StackingType::Unknown(raw) => raw,
+        }
+    }
+}
+
+/// Category of a buff, from `is_offcycle` of `BuffInfo`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuffCategory {
+    Boon,
+    Any,
+    Condition,
+    Food,
+    Upgrade,
+    Boost,
+    Trait,
+    Transform,
+    Enhancement,
+    Stance,
+    Unknown(u8),
+}
+
+impl BuffCategory {
+    pub fn from_u8(raw: u8) -> Self {
+        match raw {
+            0 => BuffCategory::Boon,
+            1 => BuffCategory::Any,
+            2 => BuffCategory::Condition,
+            4 => BuffCategory::Food,
+            6 => BuffCategory::Upgrade,
+            8 => BuffCategory::Boost,
+            11 => BuffCategory::Trait,
+            12 => BuffCategory::Transform,
+            13 => BuffCategory::Enhancement,
+            16 => BuffCategory::Stance,
+            _ => BuffCategory::Unknown(raw),
+        }
+    }
+
+    pub fn as_u8(self) -> u8 {
+        match self {
+            BuffCategory::Boon => 0,
+            BuffCategory::Any => 1,
+            BuffCategory::Condition => 2,
+            BuffCategory::Food => 4,
+            BuffCategory::Upgrade => 6,
+            BuffCategory::Boost => 8,
+            BuffCategory::Trait => 11,
+            BuffCategory::Transform => 12,
+            BuffCategory::Enhancement => 13,
+            BuffCategory::Stance => 16,
+            BuffCategory::Unknown(raw) => raw,
+        }
+    }
+}
+
+/// Stacking behaviour of a buff, the payload of a `BuffInfo` statechange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuffInfo {
+    pub stacking: StackingType,
+    pub category: BuffCategory,
+    pub max_stacks: u16,
+    /// Maximum combined duration in milliseconds
+    pub duration_cap: u32,
+    /// arcdps marks this as "probably invulnerability"
+    pub invulnerable: bool,
+    /// arcdps marks this as "probably invert"
+    pub invert: bool,
+    /// arcdps marks this as "probably resistance"
+    pub resistance: bool,
+}
+
+impl BuffInfo {
+    pub(crate) fn from_event(evt: &CbtEvent) -> Self {
+        Self {
+            stacking: StackingType::from_u8(evt.pad61),
+            category: BuffCategory::from_u8(evt.is_offcycle),
+            max_stacks: evt.src_master_instid,
+            duration_cap: evt.overstack_value,
+            invulnerable: evt.is_flanking != 0,
+            invert: evt.is_shields != 0,
+            resistance: evt.pad62 != 0,
+        }
+    }
+}
+
+/// An attribute formula of a buff, the payload of a `BuffFormula` statechange.
+///
+/// arcdps stores every field as a float, even the IDs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BuffFormula {
+    pub kind: f32,
+    pub attribute1: f32,
+    pub attribute2: f32,
+    pub parameters: [f32; 3],
+    pub trait_condition_source: f32,
+    pub trait_condition_self: f32,
+    pub content_reference: f32,
+    pub buff_condition_source: f32,
+    pub buff_condition_self: f32,
+}
+
+impl BuffFormula {
+    pub(crate) fn from_event(evt: &CbtEvent) -> Self {
+        // (float*)&time is float[9] and (float*)&src_instid is float[2]
+        let mut bytes = Vec::with_capacity(44);
+        bytes.extend(evt.time.to_le_bytes());
+        bytes.extend(evt.src_agent.to_le_bytes());
+        bytes.extend(evt.dst_agent.to_le_bytes());
+        bytes.extend(evt.value.to_le_bytes());
+        bytes.extend(evt.buff_dmg.to_le_bytes());
+        bytes.extend(evt.overstack_value.to_le_bytes());
+        bytes.extend(evt.src_instid.to_le_bytes());
+        bytes.extend(evt.dst_instid.to_le_bytes());
+        bytes.extend(evt.src_master_instid.to_le_bytes());
+        bytes.extend(evt.dst_master_instid.to_le_bytes());
+        let floats: Vec<f32> = bytes
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+        Self {
+            kind: floats[0],
+            attribute1: floats[1],
+            attribute2: floats[2],
+            parameters: [floats[3], floats[4], floats[5]],
+            trait_condition_source: floats[6],
+            trait_condition_self: floats[7],
+            content_reference: floats[8],
+            buff_condition_source: floats[9],
+            buff_condition_self: floats[10],
+        }
+    }
+}
+
+/// Everything arcdps wrote about a buff.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuffDefinition {
+    pub skill_id: u32,
+    /// `None` if the log has formulas but no `BuffInfo` for the buff
+    pub info: Option<BuffInfo>,
+    pub formulas: Vec<BuffFormula>,
+}
+
+/// The buff definitions of a log, keyed by skill ID.
+#[derive(Debug, Clone, Default)]
+pub struct BuffTable {
+    buffs: HashMap<u32, BuffDefinition>,
+}
+
+impl BuffTable {
+    pub fn get(&self, skill_id: u32) -> Option<&BuffDefinition> {
+        self.buffs.get(&skill_id)
+    }
+
+    /// Iterates over the buff definitions in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = &BuffDefinition> {
+        self.buffs.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffs.is_empty()
+    }
+
+    fn entry(&mut self, skill_id: u32) -> &mut BuffDefinition {
+        self.buffs
+            .entry(skill_id)
+            .or_insert_with(|| BuffDefinition {
+                skill_id,
+                info: None,
+                formulas: Vec::new(),
+            })
+    }
+}
+
+impl<'a> FromIterator<&'a CbtEvent> for BuffTable {
+    /// Collects the `BuffInfo` and `BuffFormula` statechanges, ignoring all other events.
+    fn from_iter<I: IntoIterator<Item = &'a CbtEvent>>(iter: I) -> Self {
+        let mut table = BuffTable::default();
+        for evt in iter {
+            if !matches!(
+                evt.statechange(),
+                CbtStateChange::BuffInfo | CbtStateChange::BuffFormula
+            ) {
+                continue;
+            }
+            match evt.decode() {
+                Event::BuffInfo { skill_id, info } => {
+                    table.entry(skill_id).info.get_or_insert(info);
+                }
+                Event::BuffFormula { skill_id, formula } => {
+                    table.entry(skill_id).formulas.push(formula)
+                }
+                _ => {}
+            }
+        }
+        table
+    }
+}
@@ -0,0 +1,119 @@
+//! Challenge and legendary mode detection for encounters.
+use crate::bossdata::{BossId, Difficulty};
+use crate::event::Event;
+use crate::evtc::{CbtStateChange, Encounter};
+
+/// Buff given to every player in normal mode raids, one stack per level of Emboldened.
+const EMBOLDENED: u32 = 68087;
+
+impl Encounter {
+    /// Determines the difficulty of the encounter.
+    ///
+    /// Challenge modes are recognized by a species that only appears in challenge mode, by the
+    /// fractal scale (see [`Fractal::challenge_mode_scale`][crate::bossdata::Fractal::challenge_mode_scale]) or by the max health of the boss
+    /// (see [`BossId::challenge_mode_health`] and [`BossId::legendary_mode_health`]). Encounters
+    /// that have a challenge mode none of these tell apart are [`Difficulty::Unknown`], unless
+    /// Emboldened shows that normal mode was played.
+    pub fn difficulty(&self) -> Difficulty {
+        let boss = BossId::from_header_id(self.header.boss_id);
+        let encounter = boss.encounter();
+        let cm_species = self.agents.iter().any(|a| {
+            a.species_id()
+                .is_some_and(|id| BossId::from_header_id(id).is_challenge_mode_species())
+        });
+        if cm_species || boss.is_challenge_mode_species() {
+            return Difficulty::ChallengeMode;
+        }
+
+        if let Some(fractal) = encounter.and_then(|e| e.fractal()) {
+            return match self.metadata.fractal_scale {
+                Some(scale) if scale == fractal.challenge_mode_scale() => Difficulty::ChallengeMode,
+                Some(_) => Difficulty::NormalMode,
+                None => Difficulty::Unknown,
+            };
+        }
+
+        let health = self.boss_max_health(boss);
+        if let Some(health) = health {
+            if boss.legendary_mode_health().is_some_and(|lm| health > lm) {
+                return Difficulty::LegendaryMode;
+            }
+            if boss.challenge_mode_health().is_some_and(|cm| health > cm) {
+                return Difficulty::ChallengeMode;
+            }
+        }
+
+        match self.emboldened_stacks() {
+            0 => {}
+            stacks => return Difficulty::Emboldened(stacks),
+        }
+        // Without a challenge mode species or a max health, normal mode is only certain for
+        // encounters that have no challenge mode
+        let known = encounter.is_none_or(|e| {
+            !e.has_challenge_mode()
+                || e.bosses().iter().any(|b| b.is_challenge_mode_species())
+                || (health.is_some() && boss.challenge_mode_health().is_some())
+        });
+        if known {
+            Difficulty::NormalMode
+        } else {
+            Difficulty::Unknown
+        }
+    }
+
+    /// The highest max health reported for an agent of the boss.
+    fn boss_max_health(&self, boss: BossId) -> Option<u64> {
+        let addrs: Vec<u64> = self
+            .agents
+            .iter()
+            .filter(|a| a.species_id() == Some(boss as u16))
+            .map(|a| a.addr)
+            .collect();
+        self.combat_log
+            .iter()
+            .filter(|evt| {
+                evt.statechange() == CbtStateChange::MaxHealthUpdate
+                    && addrs.contains(&evt.src_agent)
+            })
+            .map(|evt| evt.dst_agent)
+            .max()
+    }
+
+    /// The most Emboldened stacks the pov, or the first player without a pov, held at once.
+    fn emboldened_stacks(&self) -> u8 {
+        let Some(player) = self.pov.as_ref().or_else(|| self.players().next()) else {
+            return 0;
+        };
+        let mut stacks: u8 = 0;
+        let mut most = 0;
+        let events = self
+            .combat_log
+            .iter()
+            .filter(|evt| evt.skillid == EMBOLDENED);
+        for evt in events {
+            match evt.decode() {
+                Event::BuffApply { dst, skill_id, .. }
+                | Event::BuffInitial { dst, skill_id, .. }
+                    if dst == player.addr && skill_id == EMBOLDENED =>
+                {
+                    stacks = stacks.saturating_add(1)
+                }
+                // Removals name the agent that lost the buff in src
+                Event::BuffRemoveSingle { src, skill_id, .. }
+                | Event::BuffRemoveManual { src, skill_id, .. }
+                    if src == player.addr && skill_id == EMBOLDENED =>
+                {
+                    stacks = stacks.saturating_sub(1)
+                }
+                Event::BuffRemoveAll { src, skill_id, .. }
+                    if src == player.addr && skill_id == EMBOLDENED =>
+                {
+                    stacks = 0
+                }
+                _ => {}
+            }
+            most = most.max(stacks);
+        }
+        most
+    }
+}
@@ -0,0 +1,149 @@
+//! Error type returned when opening or parsing evtc logs.
+use std::fmt::{self, Display, Formatter};
+use std::io;
+
+use zip::result::ZipError;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The part of the evtc file that was being read when an error occurred.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Section {
+    Header,
+    AgentTable,
+    SkillTable,
+    CombatLog,
+}
+
+impl Display for Section {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match *self {
+            Section::Header => "header",
+            Section::AgentTable => "agent table",
+            Section::SkillTable => "skill table",
+            Section::CombatLog => "combat log",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Everything that can go wrong while opening or parsing a log.
+///
+/// Byte offsets are counted in the decompressed evtc data, starting at the `EVTC` magic.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The file could not be opened or read.
+    Io(io::Error),
+    /// The zip container is broken.
+    Zip(ZipError),
+    /// The zip container does not contain any file.
+    EmptyArchive,
+    /// The data is neither a zip container nor an evtc log.
+    UnknownFormat,
+    /// The header does not start with `EVTC`.
+    InvalidMagic { magic: [u8; 4] },
+    /// The arcdps version is not a valid `EVTCyyyymmdd` build date.
+    InvalidVersion { version: String },
+    /// The data ended in the middle of a section.
+    Truncated { section: Section, offset: u64 },
+    /// The combat log ends with a partial event.
+    MisalignedLog { offset: u64, trailing: usize },
+    /// Reading a section failed for another reason than a premature end of data.
+    Read {
+        section: Section,
+        offset: u64,
+        source: io::Error,
+    },
+}
+
+impl Error {
+    /// Wraps an error that occurred while reading `section` at `offset`.
+    pub(crate) fn read(section: Section, offset: u64, source: io::Error) -> Self {
+        if source.kind() == io::ErrorKind::UnexpectedEof {
+            Error::Truncated { section, offset }
+        } else {
+            Error::Read {
+                section,
+                offset,
+                source,
+            }
+        }
+    }
+
+    /// The section of the evtc file in which parsing failed, if the error is a parse error.
+    pub fn section(&self) -> Option<Section> {
+        match *self {
+            Error::InvalidMagic { .. } | Error::InvalidVersion { .. } => Some(Section::Header),
+            Error::Truncated { section, .. } | Error::Read { section, .. } => Some(section),
+            Error::MisalignedLog { .. } => Some(Section::CombatLog),
+            _ => None,
+        }
+    }
+
+    /// The byte offset at which parsing failed, if the error is a parse error.
+    pub fn offset(&self) -> Option<u64> {
+        match *self {
+            Error::InvalidMagic { .. } => Some(0),
+            Error::InvalidVersion { .. } => Some(4),
+            Error::Truncated { offset, .. }
+            | Error::Read { offset, .. }
+            | Error::MisalignedLog { offset, .. } => Some(offset),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {e}"),
+            Error::Zip(e) => write!(f, "invalid zip archive: {e}"),
+            Error::EmptyArchive => write!(f, "zip archive does not contain a file"),
+            Error::UnknownFormat => write!(f, "unknown file format, expected evtc or zip"),
+            Error::InvalidMagic { magic } => {
+                write!(f, "invalid magic number {magic:?}, expected \"EVTC\"")
+            }
+            Error::InvalidVersion { version } => {
+                write!(
+                    f,
+                    "invalid arcdps version {version:?}, expected \"EVTCyyyymmdd\""
+                )
+            }
+            Error::Truncated { section, offset } => {
+                write!(f, "unexpected end of data in {section} at byte {offset}")
+            }
+            Error::MisalignedLog { offset, trailing } => write!(
+                f,
+                "combat log ends with a partial event of {trailing} bytes at byte {offset}"
+            ),
+            Error::Read {
+                section,
+                offset,
+                source,
+            } => write!(f, "failed to read {section} at byte {offset}: {source}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) | Error::Read { source: e, .. } => Some(e),
+            Error::Zip(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<ZipError> for Error {
+    fn from(e: ZipError) -> Self {
+        Error::Zip(e)
+    }
+}
@@ -0,0 +1,644 @@
+//! Typed view of [`CbtEvent`]s.
+//!
+//! arcdps packs many different kinds of events into the same [`CbtEvent`] struct, the meaning of
+//! the fields depends on the statechange, activation and buff flags. [`CbtEvent::decode`] applies
+//! those rules once and returns an [`Event`] with the payload in named fields.
+use crate::bossdata::{EliteSpec, Profession};
+use crate::buff::{BuffFormula, BuffInfo};
+use crate::evtc::{Activation, BreakbarState, BuffRemove, CbtEvent, CbtResult, CbtStateChange};
+use crate::skill::SkillInfo;
+
+/// A decoded combat event.
+///
+/// Agent fields hold agent addresses, the time and the remaining raw fields are still available
+/// on the [`CbtEvent`] the event was decoded from.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Event {
+    /// Direct (physical) damage or healing.
+    DirectDamage {
+        src: u64,
+        dst: u64,
+        skill_id: u32,
+        damage: i32,
+        /// Damage absorbed by barrier
+        shield_damage: u32,
+        result: CbtResult,
+    },
+    /// Damage or healing over time by a buff, e.g. a condition tick.
+    BuffDamage {
+        src: u64,
+        dst: u64,
+        skill_id: u32,
+        damage: i32,
+        /// Damage absorbed by barrier
+        shield_damage: u32,
+        /// 0 if the tick hit, non-zero if it was absorbed, e.g. by invulnerability
+        result: u8,
+    },
+    /// A buff was applied.
+    BuffApply {
+        src: u64,
+        dst: u64,
+        skill_id: u32,
+        duration: i32,
+        /// Duration that was dropped because the buff was already at its cap
+        overstack: u32,
+        stack_id: u32,
+    },
+    /// All stacks of a buff were removed.
+    BuffRemoveAll {
+        /// The agent that lost the buff
+        src: u64,
+        /// The agent that caused the removal, if any
+        dst: u64,
+        skill_id: u32,
+        /// Total duration removed
+        duration: i32,
+        /// Duration of the longest stack that was removed
+        longest_stack: i32,
+        /// Number of stacks removed
+        stacks: u8,
+    },
+    /// A single stack of a buff was removed.
+    BuffRemoveSingle {
+        /// The agent that lost the buff
+        src: u64,
+        /// The agent that caused the removal, if any
+        dst: u64,
+        skill_id: u32,
+        duration: i32,
+        stack_id: u32,
+    },
+    /// A buff was removed by arcdps itself, e.g. when it expired.
+    BuffRemoveManual {
+        /// The agent that lost the buff
+        src: u64,
+        /// The agent that caused the removal, if any
+        dst: u64,
+        skill_id: u32,
+        duration: i32,
+        intensity: i32,
+    },
+    /// A skill cast started.
+    ActivationStart {
+        agent: u64,
+        skill_id: u32,
+        /// Expected duration in milliseconds
+        duration: i32,
+        /// Expected duration in milliseconds, scaled by the animation speed of the agent, e.g.
+        /// from quickness
+        scaled_duration: i32,
+    },
+    /// A skill cast was interrupted after the skill already took effect.
+    ActivationCancelFire {
+        agent: u64,
+        skill_id: u32,
+        /// Time spent in the animation in milliseconds
+        duration: i32,
+        /// Time spent in the animation, scaled like [`Event::ActivationStart::scaled_duration`]
+        scaled_duration: i32,
+    },
+    /// A skill cast was interrupted before the skill took effect.
+    ActivationCancelCancel {
+        agent: u64,
+        skill_id: u32,
+        /// Time spent in the animation in milliseconds
+        duration: i32,
+        /// Time spent in the animation, scaled like [`Event::ActivationStart::scaled_duration`]
+        scaled_duration: i32,
+    },
+    /// A skill cast completed.
+    ActivationReset {
+        agent: u64,
+        skill_id: u32,
+        /// Time spent in the animation in milliseconds
+        duration: i32,
+        /// Time spent in the animation, scaled like [`Event::ActivationStart::scaled_duration`]
+        scaled_duration: i32,
+    },
+    /// Activation kind arcdps no longer writes.
+    ActivationUnknown {
+        agent: u64,
+        skill_id: u32,
+        kind: u8,
+    },
+    EnterCombat {
+        agent: u64,
+        subgroup: u64,
+        prof: Profession,
+        elite_spec: EliteSpec,
+    },
+    ExitCombat {
+        agent: u64,
+    },
+    ChangeUp {
+        agent: u64,
+    },
+    ChangeDead {
+        agent: u64,
+    },
+    ChangeDown {
+        agent: u64,
+    },
+    Spawn {
+        agent: u64,
+    },
+    Despawn {
+        agent: u64,
+    },
+    HealthPctUpdate {
+        agent: u64,
+        /// Health in percent, 0 to 100
+        health: f32,
+    },
+    SqCombatStart {
+        server_time: u32,
+        local_time: u32,
+    },
+    LogEnd {
+        server_time: u32,
+        local_time: u32,
+    },
+    WeapSwap {
+        agent: u64,
+        set: u64,
+        old_set: i32,
+    },
+    MaxHealthUpdate {
+        agent: u64,
+        max_health: u64,
+    },
+    PointOfView {
+        agent: u64,
+    },
+    Language {
+        language: u64,
+    },
+    GwBuild {
+        build: u64,
+    },
+    ShardId {
+        shard: u64,
+    },
+    Reward {
+        reward_id: u64,
+        reward_type: i32,
+    },
+    /// Buff that was already present at the start of the log, same payload as
+    /// [`Event::BuffApply`].
+    BuffInitial {
+        src: u64,
+        dst: u64,
+        skill_id: u32,
+        duration: i32,
+        overstack: u32,
+        stack_id: u32,
+    },
+    Position {
+        agent: u64,
+        position: [f32; 3],
+    },
+    Velocity {
+        agent: u64,
+        velocity: [f32; 3],
+    },
+    Facing {
+        agent: u64,
+        facing: [f32; 2],
+    },
+    TeamChange {
+        agent: u64,
+        team: u64,
+        old_team: i32,
+    },
+    AttackTarget {
+        target: u64,
+        gadget: u64,
+        targetable: bool,
+    },
+    Targetable {
+        agent: u64,
+        targetable: bool,
+    },
+    MapId {
+        map_id: u64,
+    },
+    ReplInfo,
+    StackActive {
+        agent: u64,
+        stack_id: u64,
+        duration: i32,
+    },
+    StackReset {
+        agent: u64,
+        stack_id: u32,
+        duration: i32,
+    },
+    Guild {
+        agent: u64,
+        guid: [u8; 16],
+    },
+    BuffInfo {
+        skill_id: u32,
+        info: BuffInfo,
+    },
+    BuffFormula {
+        skill_id: u32,
+        formula: BuffFormula,
+    },
+    SkillInfo {
+        skill_id: u32,
+        info: SkillInfo,
+    },
+    SkillTiming {
+        skill_id: u32,
+        timing_type: u64,
+        /// Time since activation in milliseconds
+        at: u64,
+    },
+    BreakbarState {
+        agent: u64,
+        state: BreakbarState,
+    },
+    BreakbarPercent {
+        agent: u64,
+        /// Breakbar in percent, 0 to 100
+        breakbar: f32,
+    },
+    Integrity,
+    Marker {
+        agent: u64,
+        /// Marker ID, 0 if the marker was removed
+        marker_id: i32,
+        is_commander: bool,
+    },
+    BarrierPctUpdate {
+        agent: u64,
+        /// Barrier in percent of max health, 0 to 100
+        barrier: f32,
+    },
+    StatReset,
+    Extension,
+    ApiDelayed,
+    InstanceStart {
+        /// Milliseconds since the map instance was created
+        instance_age: u64,
+    },
+    RateHealth,
+    Last90BeforeDown,
+    Effect,
+    IdToGuid,
+    LogNpcUpdate {
+        species_id: u64,
+        agent: u64,
+        server_time: u32,
+    },
+    IdleEvent,
+    ExtensionCombat,
+    FractalScale {
+        scale: u64,
+    },
+    Effect2,
+    Ruleset {
+        flags: u64,
+    },
+    SquadMarker,
+    ArcBuild {
+        /// The arcdps build string, e.g. `20241030.152507-507-x64`
+        build: String,
+    },
+    Glider {
+        agent: u64,
+        deployed: bool,
+    },
+    StunBreak {
+        agent: u64,
+        /// Remaining duration of the disable in milliseconds
+        remaining: i32,
+    },
+    /// Statechange that is newer than this crate.
+    UnknownStateChange(u8),
+}
+
+impl CbtEvent {
+    /// Decodes the event according to the arcdps rules.
+    pub fn decode(&self) -> Event {
+        if self.statechange() != CbtStateChange::None {
+            self.decode_statechange()
+        } else if self.activation() != Activation::None {
+            self.decode_activation()
+        } else if self.buffremove() != BuffRemove::None {
+            self.decode_buffremove()
+        } else if self.buff != 0 && self.value == 0 {
+            Event::BuffDamage {
+                src: self.src_agent,
+                dst: self.dst_agent,
+                skill_id: self.skillid,
+                damage: self.buff_dmg,
+                shield_damage: self.overstack_value,
+                result: self.result,
+            }
+        } else if self.buff != 0 {
+            Event::BuffApply {
+                src: self.src_agent,
+                dst: self.dst_agent,
+                skill_id: self.skillid,
+                duration: self.value,
+                overstack: self.overstack_value,
+                stack_id: self.pad_u32(),
+            }
+        } else {
+            Event::DirectDamage {
+                src: self.src_agent,
+                dst: self.dst_agent,
+                skill_id: self.skillid,
+                damage: self.value,
+                shield_damage: self.overstack_value,
+                result: self.result(),
+            }
+        }
+    }
+
+    fn decode_activation(&self) -> Event {
+        let (agent, skill_id) = (self.src_agent, self.skillid);
+        let (duration, scaled_duration) = (self.value, self.buff_dmg);
+        match self.activation() {
+            Activation::Start => Event::ActivationStart {
+                agent,
+                skill_id,
+                duration,
+                scaled_duration,
+            },
+            Activation::CancelFire => Event::ActivationCancelFire {
+                agent,
+                skill_id,
+                duration,
+                scaled_duration,
+            },
+            Activation::CancelCancel => Event::ActivationCancelCancel {
+                agent,
+                skill_id,
+                duration,
+                scaled_duration,
+            },
+            Activation::Reset => Event::ActivationReset {
+                agent,
+                skill_id,
+                duration,
+                scaled_duration,
+            },
+            kind => Event::ActivationUnknown {
+                agent,
+                skill_id,
+                kind: kind.as_u8(),
+            },
+        }
+    }
+
+    fn decode_buffremove(&self) -> Event {
+        let (src, dst, skill_id) = (self.src_agent, self.dst_agent, self.skillid);
+        match self.buffremove() {
+            BuffRemove::All => Event::BuffRemoveAll {
+                src,
+                dst,
+                skill_id,
+                duration: self.value,
+                longest_stack: self.buff_dmg,
+                stacks: self.result,
+            },
+            BuffRemove::Single => Event::BuffRemoveSingle {
+                src,
+                dst,
+                skill_id,
+                duration: self.value,
+                stack_id: self.pad_u32(),
+            },
+            _ => Event::BuffRemoveManual {
+                src,
+                dst,
+                skill_id,
+                duration: self.value,
+                intensity: self.buff_dmg,
+            },
+        }
+    }
+
+    fn decode_statechange(&self) -> Event {
+        use CbtStateChange as SC;
+        let agent = self.src_agent;
+        match self.statechange() {
+            SC::EnterCombat => Event::EnterCombat {
+                agent,
+                subgroup: self.dst_agent,
+                prof: Profession::from_evtc(self.value as u32),
+                elite_spec: EliteSpec::from_evtc(self.buff_dmg as u32),
+            },
+            SC::ExitCombat => Event::ExitCombat { agent },
+            SC::ChangeUp => Event::ChangeUp { agent },
+            SC::ChangeDead => Event::ChangeDead { agent },
+            SC::ChangeDown => Event::ChangeDown { agent },
+            SC::Spawn => Event::Spawn { agent },
+            SC::Despawn => Event::Despawn { agent },
+            SC::HealthPctUpdate => Event::HealthPctUpdate {
+                agent,
+                health: self.dst_agent as f32 / 100.0,
+            },
+            SC::SqCombatStart => Event::SqCombatStart {
+                server_time: self.value as u32,
+                local_time: self.buff_dmg as u32,
+            },
+            SC::LogEnd => Event::LogEnd {
+                server_time: self.value as u32,
+                local_time: self.buff_dmg as u32,
+            },
+            SC::WeapSwap => Event::WeapSwap {
+                agent,
+                set: self.dst_agent,
+                old_set: self.value,
+            },
+            SC::MaxHealthUpdate => Event::MaxHealthUpdate {
+                agent,
+                max_health: self.dst_agent,
+            },
+            SC::PointOfView => Event::PointOfView { agent },
+            SC::Language => Event::Language { language: agent },
+            SC::GwBuild => Event::GwBuild { build: agent },
+            SC::ShardId => Event::ShardId { shard: agent },
+            SC::Reward => Event::Reward {
+                reward_id: self.dst_agent,
+                reward_type: self.value,
+            },
+            SC::BuffInitial => Event::BuffInitial {
+                src: self.src_agent,
+                dst: self.dst_agent,
+                skill_id: self.skillid,
+                duration: self.value,
+                overstack: self.overstack_value,
+                stack_id: self.pad_u32(),
+            },
+            SC::Position => Event::Position {
+                agent,
+                position: self.dst_floats(),
+            },
+            SC::Velocity => Event::Velocity {
+                agent,
+                velocity: self.dst_floats(),
+            },
+            SC::Facing => {
+                let [x, y, _] = self.dst_floats();
+                Event::Facing {
+                    agent,
+                    facing: [x, y],
+                }
+            }
+            SC::TeamChange => Event::TeamChange {
+                agent,
+                team: self.dst_agent,
+                old_team: self.value,
+            },
+            SC::AttackTarget => Event::AttackTarget {
+                target: agent,
+                gadget: self.dst_agent,
+                targetable: self.value != 0,
+            },
+            SC::Targetable => Event::Targetable {
+                agent,
+                targetable: self.dst_agent != 0,
+            },
+            SC::MapId => Event::MapId { map_id: agent },
+            SC::ReplInfo => Event::ReplInfo,
+            SC::StackActive => Event::StackActive {
+                agent,
+                stack_id: self.dst_agent,
+                duration: self.value,
+            },
+            SC::StackReset => Event::StackReset {
+                agent,
+                stack_id: self.pad_u32(),
+                duration: self.value,
+            },
+            SC::Guild => {
+                let mut guid = [0; 16];
+                guid[0..8].copy_from_slice(&self.dst_agent.to_le_bytes());
+                guid[8..12].copy_from_slice(&self.value.to_le_bytes());
+                guid[12..16].copy_from_slice(&self.buff_dmg.to_le_bytes());
+                Event::Guild { agent, guid }
+            }
+            SC::BuffInfo => Event::BuffInfo {
+                skill_id: self.skillid,
+                info: BuffInfo::from_event(self),
+            },
+            SC::BuffFormula => Event::BuffFormula {
+                skill_id: self.skillid,
+                formula: BuffFormula::from_event(self),
+            },
+            SC::SkillInfo => Event::SkillInfo {
+                skill_id: self.skillid,
+                info: SkillInfo::from_event(self),
+            },
+            SC::SkillTiming => Event::SkillTiming {
+                skill_id: self.skillid,
+                timing_type: self.src_agent,
+                at: self.dst_agent,
+            },
+            SC::BreakbarState => Event::BreakbarState {
+                agent,
+                state: BreakbarState::from_u64(self.dst_agent),
+            },
+            SC::BreakbarPercent => Event::BreakbarPercent {
+                agent,
+                // arcdps writes a 0 to 1 fraction
+                breakbar: f32::from_bits(self.value as u32) * 100.0,
+            },
+            SC::Integrity => Event::Integrity,
+            SC::Marker => Event::Marker {
+                agent,
+                marker_id: self.value,
+                is_commander: self.buff != 0,
+            },
+            SC::BarrierPctUpdate => Event::BarrierPctUpdate {
+                agent,
+                barrier: self.dst_agent as f32 / 100.0,
+            },
+            SC::StatReset => Event::StatReset,
+            SC::Extension => Event::Extension,
+            SC::ApiDelayed => Event::ApiDelayed,
+            SC::InstanceStart => Event::InstanceStart {
+                instance_age: self.src_agent,
+            },
+            SC::RateHealth => Event::RateHealth,
+            SC::Last90BeforeDown => Event::Last90BeforeDown,
+            SC::Effect => Event::Effect,
+            SC::IdToGuid => Event::IdToGuid,
+            SC::LogNpcUpdate => Event::LogNpcUpdate {
+                species_id: self.src_agent,
+                agent: self.dst_agent,
+                server_time: self.value as u32,
+            },
+            SC::IdleEvent => Event::IdleEvent,
+            SC::ExtensionCombat => Event::ExtensionCombat,
+            SC::FractalScale => Event::FractalScale { scale: agent },
+            SC::Effect2 => Event::Effect2,
+            SC::Ruleset => Event::Ruleset { flags: agent },
+            SC::SquadMarker => Event::SquadMarker,
+            SC::ArcBuild => Event::ArcBuild {
+                build: self.arc_build(),
+            },
+            SC::Glider => Event::Glider {
+                agent,
+                deployed: self.value != 0,
+            },
+            SC::StunBreak => Event::StunBreak {
+                agent,
+                remaining: self.value,
+            },
+            SC::None | SC::Unknown(_) => Event::UnknownStateChange(self.is_statechange),
+        }
+    }
+
+    /// `(char*)&src_agent`, the null terminated build string of an `ArcBuild` statechange.
+    pub(crate) fn arc_build(&self) -> String {
+        let mut bytes = Vec::with_capacity(48);
+        bytes.extend(self.src_agent.to_le_bytes());
+        bytes.extend(self.dst_agent.to_le_bytes());
+        bytes.extend(self.value.to_le_bytes());
+        bytes.extend(self.buff_dmg.to_le_bytes());
+        bytes.extend(self.overstack_value.to_le_bytes());
+        bytes.extend(self.skillid.to_le_bytes());
+        for instid in [
+            self.src_instid,
+            self.dst_instid,
+            self.src_master_instid,
+            self.dst_master_instid,
+        ] {
+            bytes.extend(instid.to_le_bytes());
+        }
+        bytes.extend([
+            self.iff,
+            self.buff,
+            self.result,
+            self.is_activation,
+            self.is_buffremove,
+            self.is_ninety,
+            self.is_fifty,
+            self.is_moving,
+        ]);
+        let build = bytes.split(|&c| c == 0).next().unwrap_or_default();
+        String::from_utf8_lossy(build).into_owned()
+    }
+
+    /// `pad61` to `pad64` as a little-endian `u32`, used for buff stack IDs.
+    fn pad_u32(&self) -> u32 {
+        u32::from_le_bytes([self.pad61, self.pad62, self.pad63, self.pad64])
+    }
+
+    /// `(float*)&dst_agent`, which spans `dst_agent` and `value`.
+    fn dst_floats(&self) -> [f32; 3] {
+        [
+            f32::from_bits(self.dst_agent as u32),
+            f32::from_bits((self.dst_agent >> 32) as u32),
+            f32::from_bits(self.value as u32),
+        ]
+    }
+}
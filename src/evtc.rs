@@ -1,11 +1,15 @@
-use byteorder::{LittleEndian, ReadBytesExt};
-use std::convert::TryInto;
+use byteorder::{ByteOrder, LittleEndian, ReadBytesExt};
+use std::collections::HashMap;
 use std::fmt::Formatter;
 use std::io::{self, Read};
-use std::mem;
 use std::str;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use crate::bossdata::{EliteSpec, Profession};
+use crate::bossdata::{BossId, EliteSpec, Language, Profession, Ruleset};
+use crate::buff::BuffTable;
+use crate::error::{Error, Result, Section};
+use crate::event::Event;
+use crate::skill::SkillTable;
 
 #[repr(C)]
 #[derive(Debug)]
@@ -22,166 +26,591 @@ pub struct EvtcAgent {
     name: [u8; 64],
 }
 
-#[repr(C, packed)]
+#[repr(C)]
 #[derive(Debug)]
 pub struct EvtcSkill {
     id: i32,
     name: [u8; 64],
 }
 
-#[repr(C, packed)]
-#[derive(Debug)]
-struct RawHeader {
-    evtc_magic: [u8; 4],
-    version: [u8; 8],
-    revision: u8,
-    boss_id: u16,
-    _unused: u8,
+/// Size of the evtc header, including the trailing unused byte.
+const HEADER_SIZE: usize = 16;
+
+impl EvtcAgent {
+    /// Size of an agent in the agent table, including 4 bytes of trailing padding.
+    pub const SIZE: usize = 96;
+
+    /// Decodes an agent table entry.
+    pub fn from_bytes(buf: &[u8; Self::SIZE]) -> Self {
+        Self {
+            addr: LittleEndian::read_u64(&buf[0..8]),
+            prof: LittleEndian::read_u32(&buf[8..12]),
+            is_elite: LittleEndian::read_u32(&buf[12..16]),
+            toughness: LittleEndian::read_u16(&buf[16..18]),
+            concentration: LittleEndian::read_u16(&buf[18..20]),
+            healing: LittleEndian::read_u16(&buf[20..22]),
+            hitbox_width: LittleEndian::read_u16(&buf[22..24]),
+            condition: LittleEndian::read_u16(&buf[24..26]),
+            hitbox_height: LittleEndian::read_u16(&buf[26..28]),
+            name: buf[28..92].try_into().unwrap(),
+        }
+    }
+
+    pub fn addr(&self) -> u64 {
+        self.addr
+    }
+
+    /// Profession for players, species or gadget ID otherwise
+    pub fn prof(&self) -> u32 {
+        self.prof
+    }
+
+    /// Elite specialization for players, `0xFFFFFFFF` for NPCs and gadgets
+    pub fn is_elite(&self) -> u32 {
+        self.is_elite
+    }
+
+    pub fn toughness(&self) -> u16 {
+        self.toughness
+    }
+
+    pub fn concentration(&self) -> u16 {
+        self.concentration
+    }
+
+    pub fn healing(&self) -> u16 {
+        self.healing
+    }
+
+    pub fn condition(&self) -> u16 {
+        self.condition
+    }
+
+    pub fn hitbox_width(&self) -> u16 {
+        self.hitbox_width
+    }
+
+    pub fn hitbox_height(&self) -> u16 {
+        self.hitbox_height
+    }
+
+    /// The raw, null separated name bytes.
+    pub fn name(&self) -> &[u8; 64] {
+        &self.name
+    }
 }
 
-#[derive(Debug)]
+impl EvtcSkill {
+    /// Size of a skill in the skill table.
+    pub const SIZE: usize = 68;
+
+    /// Decodes a skill table entry.
+    pub fn from_bytes(buf: &[u8; Self::SIZE]) -> Self {
+        Self {
+            id: LittleEndian::read_i32(&buf[0..4]),
+            name: buf[4..68].try_into().unwrap(),
+        }
+    }
+
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    /// The raw, null terminated name bytes.
+    pub fn name(&self) -> &[u8; 64] {
+        &self.name
+    }
+}
+
+/// The arcdps build that wrote a log, identified by its build date.
+///
+/// Versions are ordered chronologically, so features can be gated on the build that introduced
+/// them:
+///
+/// ```
+/// # use revtc::evtc::ArcVersion;
+/// let version: ArcVersion = "EVTC20241030".parse().unwrap();
+/// assert!(version >= ArcVersion::new(2024, 8, 27));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ArcVersion {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl ArcVersion {
+    pub const fn new(year: u16, month: u8, day: u8) -> Self {
+        Self { year, month, day }
+    }
+
+    /// Parses the 8 version bytes of the header, e.g. `b"20241030"`.
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 8 || !bytes.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        let num = |range: std::ops::Range<usize>| {
+            bytes[range]
+                .iter()
+                .fold(0u16, |acc, &d| acc * 10 + u16::from(d - b'0'))
+        };
+        let version = Self::new(num(0..4), num(4..6) as u8, num(6..8) as u8);
+        ((1..=12).contains(&version.month) && (1..=31).contains(&version.day)).then_some(version)
+    }
+}
+
+impl str::FromStr for ArcVersion {
+    type Err = Error;
+
+    /// Parses a version with or without the `EVTC` prefix, e.g. `EVTC20241030` or `20241030`.
+    fn from_str(s: &str) -> Result<Self> {
+        let digits = s.strip_prefix("EVTC").unwrap_or(s);
+        Self::from_bytes(digits.as_bytes()).ok_or_else(|| Error::InvalidVersion {
+            version: s.to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for ArcVersion {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "EVTC{:04}{:02}{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Header {
-    pub version: String,
+    pub version: ArcVersion,
     pub revision: u8,
     pub boss_id: u16,
 }
 
-fn read_header(file: &mut impl Read) -> io::Result<Header> {
-    let mut header: RawHeader = unsafe { mem::zeroed() };
-    let buf: &mut [u8; mem::size_of::<RawHeader>()] = unsafe { std::mem::transmute(&mut header) };
-    file.read_exact(buf)?;
-    if header.evtc_magic != *b"EVTC" {
-        return Err(io::Error::other("Invalid magic number"));
+fn read_header(file: &mut CountingReader<impl Read>) -> Result<Header> {
+    let mut buf = [0; HEADER_SIZE];
+    file.read_exact(&mut buf)
+        .map_err(|e| Error::read(Section::Header, 0, e))?;
+    if buf[0..4] != *b"EVTC" {
+        return Err(Error::InvalidMagic {
+            magic: buf[0..4].try_into().unwrap(),
+        });
     }
 
-    let version = str::from_utf8(header.version.as_ref()).unwrap_or("");
-    let revision = header.revision;
-    let boss_id = header.boss_id;
+    let version = ArcVersion::from_bytes(&buf[4..12]).ok_or_else(|| Error::InvalidVersion {
+        version: String::from_utf8_lossy(&buf[4..12]).into_owned(),
+    })?;
+    let revision = buf[12];
+    let boss_id = LittleEndian::read_u16(&buf[13..15]);
 
     Ok(Header {
-        version: version.to_string(),
+        version,
         revision,
         boss_id,
     })
 }
 
-#[derive(Debug, Clone)]
-pub struct Agent {
-    pub addr: u64,
+/// What kind of entity an agent is, decoded from the `prof` and `is_elite` fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AgentKind {
+    Player(Player),
+    /// A non-player character, e.g. a boss or an add
+    Npc {
+        species_id: u16,
+    },
+    /// A gadget, e.g. a spawned object or an attack target
+    Gadget {
+        id: u16,
+    },
+}
+
+/// Player specific agent data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Player {
     pub prof: Profession,
     pub elite_spec: EliteSpec,
-    pub character_name: String,
     pub account_name: String,
     pub subgroup: String,
 }
 
-impl TryFrom<&EvtcAgent> for Agent {
-    type Error = anyhow::Error;
+#[derive(Debug, Clone)]
+pub struct Agent {
+    pub addr: u64,
+    /// Character name for players, NPC or gadget name otherwise
+    pub name: String,
+    pub kind: AgentKind,
+    /// Toughness rating, 0 to 10 relative to the squad
+    pub toughness: u16,
+    /// Concentration rating, 0 to 10 relative to the squad
+    pub concentration: u16,
+    /// Healing power rating, 0 to 10 relative to the squad
+    pub healing: u16,
+    /// Condition damage rating, 0 to 10 relative to the squad
+    pub condition: u16,
+    pub hitbox_width: u16,
+    pub hitbox_height: u16,
+    /// Instance ID used in events, 0 if the agent never appeared in the combat log
+    pub instid: u16,
+    /// Times of the first and last event involving this agent, `None` if the agent never
+    /// appeared in the combat log
+    pub aware: Option<(u64, u64)>,
+    master: Option<u64>,
+}
 
-    fn try_from(raw: &EvtcAgent) -> Result<Self, Self::Error> {
-        if raw.is_elite != 0xFFFFFFFF {
-            let mut it = raw.name.iter();
-            let character_name: String =
-                String::from_utf8(it.by_ref().take_while(|&&c| c != 0).cloned().collect())?;
-            let account_name: String =
-                String::from_utf8(it.by_ref().take_while(|&&c| c != 0).cloned().collect())?
-                    .trim_start_matches(':')
-                    .to_string();
-            let subgroup: String =
-                String::from_utf8(it.by_ref().take_while(|&&c| c != 0).cloned().collect())?;
-            Ok(Self {
-                addr: raw.addr,
+/// Identifies an agent either by address or by instance ID, see [`Encounter::agent_for`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AgentId {
+    Addr(u64),
+    InstId(u16),
+}
+
+impl From<u64> for AgentId {
+    fn from(addr: u64) -> Self {
+        AgentId::Addr(addr)
+    }
+}
+
+impl From<u16> for AgentId {
+    fn from(instid: u16) -> Self {
+        AgentId::InstId(instid)
+    }
+}
+
+impl Agent {
+    /// The player data, if this agent is a player.
+    pub fn player(&self) -> Option<&Player> {
+        match &self.kind {
+            AgentKind::Player(player) => Some(player),
+            _ => None,
+        }
+    }
+
+    pub fn is_player(&self) -> bool {
+        self.player().is_some()
+    }
+
+    /// Address of the agent that owns this one, e.g. the player that summoned a pet or clone.
+    ///
+    /// This is only the direct master, use [`Encounter::root_master`] to follow chains like a
+    /// clone of a minion. The master is resolved once, at the first event that names it, since
+    /// every spawned minion gets its own address and cannot change owners.
+    pub fn master(&self) -> Option<u64> {
+        self.master
+    }
+
+    /// Whether the agent was tracked by arcdps at `time`.
+    pub fn is_aware_at(&self, time: u64) -> bool {
+        self.aware
+            .is_some_and(|(first, last)| first <= time && time <= last)
+    }
+
+    /// The species ID, if this agent is an NPC.
+    pub fn species_id(&self) -> Option<u16> {
+        match self.kind {
+            AgentKind::Npc { species_id } => Some(species_id),
+            _ => None,
+        }
+    }
+}
+
+impl From<&EvtcAgent> for Agent {
+    fn from(raw: &EvtcAgent) -> Self {
+        // names are null separated, players have "character\0:account\0subgroup\0"
+        let mut parts = raw
+            .name
+            .split(|&c| c == 0)
+            .map(|part| String::from_utf8_lossy(part).into_owned());
+        let name = parts.next().unwrap_or_default();
+        let kind = if raw.is_elite != 0xFFFFFFFF {
+            AgentKind::Player(Player {
                 prof: Profession::from_evtc(raw.prof),
                 elite_spec: EliteSpec::from_evtc(raw.is_elite),
-                character_name,
-                account_name,
-                subgroup,
+                account_name: parts
+                    .next()
+                    .unwrap_or_default()
+                    .trim_start_matches(':')
+                    .to_string(),
+                subgroup: parts.next().unwrap_or_default(),
             })
+        } else if raw.prof >> 16 == 0xFFFF {
+            AgentKind::Gadget {
+                id: raw.prof as u16,
+            }
         } else {
-            anyhow::bail!("Not a player agent");
+            AgentKind::Npc {
+                species_id: raw.prof as u16,
+            }
+        };
+        Self {
+            addr: raw.addr,
+            name,
+            kind,
+            toughness: raw.toughness,
+            concentration: raw.concentration,
+            healing: raw.healing,
+            condition: raw.condition,
+            hitbox_width: raw.hitbox_width,
+            hitbox_height: raw.hitbox_height,
+            instid: 0,
+            aware: None,
+            master: None,
         }
     }
 }
-// we only care about players
-fn read_agents(file: &mut impl Read, count: u32) -> io::Result<Vec<Agent>> {
+
+fn read_count(file: &mut CountingReader<impl Read>, section: Section) -> Result<u32> {
+    let offset = file.count;
+    file.read_u32::<LittleEndian>()
+        .map_err(|e| Error::read(section, offset, e))
+}
+
+fn read_agents(file: &mut CountingReader<impl Read>, count: u32) -> Result<Vec<Agent>> {
     let mut agents = Vec::new();
     for _ in 0..count {
-        let mut agent: EvtcAgent = unsafe { mem::zeroed() };
-        let agent_bytes: &mut [u8; mem::size_of::<EvtcAgent>()] =
-            unsafe { mem::transmute(&mut agent) };
-        file.read_exact(agent_bytes)?;
-
-        if agent.is_elite != 0xFFFFFFFF {
-            if let Ok(a) = (&agent).try_into() {
-                agents.push(a);
-            }
-        }
+        let offset = file.count;
+        let mut buf = [0; EvtcAgent::SIZE];
+        file.read_exact(&mut buf)
+            .map_err(|e| Error::read(Section::AgentTable, offset, e))?;
+        agents.push(Agent::from(&EvtcAgent::from_bytes(&buf)));
     }
     Ok(agents)
 }
 
-fn read_agents_raw(file: &mut impl Read, count: u32) -> io::Result<Vec<EvtcAgent>> {
+fn read_agents_raw(file: &mut CountingReader<impl Read>, count: u32) -> Result<Vec<EvtcAgent>> {
     let mut agents = Vec::new();
     for _ in 0..count {
-        let mut agent: EvtcAgent = unsafe { mem::zeroed() };
-        let agent_bytes: &mut [u8; mem::size_of::<EvtcAgent>()] =
-            unsafe { mem::transmute(&mut agent) };
-        file.read_exact(agent_bytes)?;
+        let offset = file.count;
+        let mut buf = [0; EvtcAgent::SIZE];
+        file.read_exact(&mut buf)
+            .map_err(|e| Error::read(Section::AgentTable, offset, e))?;
+        let agent = EvtcAgent::from_bytes(&buf);
 
         agents.push(agent);
     }
     Ok(agents)
 }
 
-fn read_skills(file: &mut impl Read, count: u32) -> io::Result<Vec<EvtcSkill>> {
-    let mut skill_bytes = vec![0; count as usize * mem::size_of::<EvtcSkill>()];
-    file.read_exact(&mut skill_bytes)?;
-    skill_bytes.shrink_to_fit();
-    let skills: Vec<EvtcSkill> = unsafe {
-        Vec::from_raw_parts(
-            skill_bytes.as_ptr() as *mut EvtcSkill,
-            skill_bytes.len() / mem::size_of::<EvtcSkill>(),
-            skill_bytes.capacity() / mem::size_of::<EvtcSkill>(),
-        )
-    };
-    // leak the vec to prevent it from being dropped
-    // because we are basically transferring ownership
-    // to skills
-    skill_bytes.leak();
+fn read_skills(file: &mut CountingReader<impl Read>, count: u32) -> Result<Vec<EvtcSkill>> {
+    let mut skills = Vec::new();
+    for _ in 0..count {
+        let offset = file.count;
+        let mut buf = [0; EvtcSkill::SIZE];
+        file.read_exact(&mut buf)
+            .map_err(|e| Error::read(Section::SkillTable, offset, e))?;
+        skills.push(EvtcSkill::from_bytes(&buf));
+    }
     Ok(skills)
 }
 
-fn read_log(file: &mut impl Read) -> io::Result<Vec<CbtEvent>> {
-    let mut combat_log = vec![];
-    file.read_to_end(&mut combat_log)?;
-    if combat_log.len() % mem::size_of::<CbtEvent>() != 0 {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidData,
-            "Combat log length is not a multiple of CbtEvent",
-        ));
-    }
-    // make sure capacity is a multiple of CbtEvent
-    combat_log.shrink_to_fit();
-    let cbtlog: Vec<CbtEvent> = unsafe {
-        Vec::from_raw_parts(
-            combat_log.as_ptr() as *mut CbtEvent,
-            combat_log.len() / mem::size_of::<CbtEvent>(),
-            combat_log.capacity() / mem::size_of::<CbtEvent>(),
-        )
-    };
-    // leak the vec to prevent it from being dropped
-    // because we are basically transferring ownership
-    // to cbtlog
-    combat_log.leak();
-    Ok(cbtlog)
+fn read_log(file: &mut CountingReader<impl Read>, revision: u8) -> Result<Vec<CbtEvent>> {
+    let offset = file.count;
+    EventStream::at_offset(file, offset, revision).collect()
 }
 
+/// Reads the combat log one [`CbtEvent`] at a time.
+///
+/// Only a single event is kept in memory, so arbitrarily large logs can be processed in constant
+/// memory. The stream ends after the last complete event, a trailing partial event yields an
+/// [`Error::MisalignedLog`] error.
+///
+/// Events are decoded according to the header revision, revision 0 logs are converted to the
+/// revision 1 layout of [`CbtEvent`].
+pub struct EventStream<R> {
+    rdr: R,
+    offset: u64,
+    revision: u8,
+    done: bool,
+}
+
+impl<R: Read> EventStream<R> {
+    /// Creates a stream over `rdr`, which has to be positioned at the start of the combat log.
+    ///
+    /// Offsets in errors are relative to the start of `rdr`.
+    pub fn new(rdr: R) -> Self {
+        Self::with_revision(rdr, 1)
+    }
+
+    /// Creates a stream over `rdr` that decodes events of the given [`Header::revision`].
+    pub fn with_revision(rdr: R, revision: u8) -> Self {
+        Self::at_offset(rdr, 0, revision)
+    }
+
+    fn at_offset(rdr: R, offset: u64, revision: u8) -> Self {
+        Self {
+            rdr,
+            offset,
+            revision,
+            done: false,
+        }
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.rdr
+    }
+
+    fn read_event(&mut self) -> Result<Option<CbtEvent>> {
+        let mut buf = [0; CbtEvent::SIZE];
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.rdr.read(&mut buf[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(Error::read(Section::CombatLog, self.offset, e)),
+            }
+        }
+        if filled == 0 {
+            return Ok(None);
+        }
+        if filled < buf.len() {
+            return Err(Error::MisalignedLog {
+                offset: self.offset,
+                trailing: filled,
+            });
+        }
+        self.offset += buf.len() as u64;
+        if self.revision == 0 {
+            Ok(Some(CbtEvent::from_bytes_rev0(&buf)))
+        } else {
+            Ok(Some(CbtEvent::from_bytes(&buf)))
+        }
+    }
+}
+
+impl<R: Read> Iterator for EventStream<R> {
+    type Item = Result<CbtEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let evt = self.read_event().transpose();
+        if !matches!(evt, Some(Ok(_))) {
+            self.done = true;
+        }
+        evt
+    }
+}
+
+impl<R: Read> std::iter::FusedIterator for EventStream<R> {}
+
 pub struct Encounter {
     pub header: Header,
     pub agents: Vec<Agent>,
-    pub skills: Vec<EvtcSkill>,
+    pub skills: SkillTable,
+    pub buffs: BuffTable,
     pub combat_log: Vec<CbtEvent>,
     pub pov: Option<Agent>,
+    pub metadata: EncounterMetadata,
+    /// Index into `agents` by address
+    by_addr: HashMap<u64, usize>,
+}
+/// A `SqCombatStart` or `LogEnd` statechange, which ties the event clock to wall-clock time.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LogBoundary {
+    /// Event `time` of the statechange
+    pub time: u64,
+    /// Server UNIX timestamp in seconds
+    pub server_time: u32,
+    /// Local UNIX timestamp in seconds, from the clock of the recording player
+    pub local_time: u32,
+}
+
+impl LogBoundary {
+    fn from_event(evt: &CbtEvent) -> Self {
+        Self {
+            time: evt.time,
+            server_time: evt.value as u32,
+            local_time: evt.buff_dmg as u32,
+        }
+    }
+
+    /// The server timestamp as wall-clock time.
+    pub fn server_wall_clock(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.server_time.into())
+    }
+
+    /// The local timestamp as wall-clock time.
+    pub fn local_wall_clock(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.local_time.into())
+    }
+}
+
+/// Information about the game and arcdps that recorded a log, taken from the first statechange
+/// of each kind.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncounterMetadata {
+    pub map_id: Option<u32>,
+    pub gw_build: Option<u64>,
+    pub language: Option<Language>,
+    pub shard_id: Option<u64>,
+    /// The arcdps build string, more precise than [`Header::version`]
+    pub arc_build: Option<String>,
+    pub ruleset: Option<Ruleset>,
+    pub fractal_scale: Option<u64>,
+    /// Milliseconds since the map instance was created
+    pub instance_start: Option<u64>,
+    pub log_start: Option<LogBoundary>,
+    /// The last `LogEnd`, `None` if arcdps did not finish writing the log
+    pub log_end: Option<LogBoundary>,
+}
+
+impl EncounterMetadata {
+    /// Records the payload of a metadata statechange, keeping values that were already set.
+    fn update(&mut self, evt: &CbtEvent) {
+        let value = evt.src_agent;
+        match evt.statechange() {
+            CbtStateChange::MapId => {
+                self.map_id.get_or_insert(value as u32);
+            }
+            CbtStateChange::GwBuild => {
+                self.gw_build.get_or_insert(value);
+            }
+            CbtStateChange::Language if self.language.is_none() => {
+                self.language = Language::from_evtc(value);
+            }
+            CbtStateChange::ShardId => {
+                self.shard_id.get_or_insert(value);
+            }
+            CbtStateChange::ArcBuild => {
+                self.arc_build.get_or_insert_with(|| evt.arc_build());
+            }
+            CbtStateChange::Ruleset if self.ruleset.is_none() => {
+                self.ruleset = Ruleset::from_evtc(value);
+            }
+            CbtStateChange::FractalScale => {
+                self.fractal_scale.get_or_insert(value);
+            }
+            CbtStateChange::InstanceStart => {
+                self.instance_start.get_or_insert(value);
+            }
+            CbtStateChange::SqCombatStart => {
+                self.log_start.get_or_insert(LogBoundary::from_event(evt));
+            }
+            CbtStateChange::LogEnd => self.log_end = Some(LogBoundary::from_event(evt)),
+            _ => {}
+        }
+    }
+}
+/// An encounter whose combat log has not been read yet.
+///
+/// Created by [`read_encounter_streaming`], the combat events are read lazily from `events`.
+pub struct StreamingEncounter<R> {
+    pub header: Header,
+    pub agents: Vec<Agent>,
+    pub skills: SkillTable,
+    pub events: EventStream<R>,
+}
+/// Encounter metadata read from the start of a log, see [`read_summary`].
+#[derive(Debug, Clone)]
+pub struct EncounterSummary {
+    pub header: Header,
+    pub agents: Vec<Agent>,
+    pub pov: Option<Agent>,
+    pub metadata: EncounterMetadata,
+    /// Number of evtc bytes that were decoded
+    ///
+    /// For zipped logs this counts decompressed bytes, which says nothing about how much of the
+    /// compressed file was read.
+    pub evtc_bytes_read: u64,
 }
 pub struct RawEncounter {
     pub header: Header,
@@ -189,13 +618,145 @@ pub struct RawEncounter {
     pub skills: Vec<EvtcSkill>,
     pub combat_log: Vec<CbtEvent>,
     pub pov: Option<Agent>,
+    pub metadata: EncounterMetadata,
 }
 
 impl Encounter {
-    /// Deletes all cbtlog and skills
+    /// Deletes all cbtlog, skills and buffs
     pub fn shrink(&mut self) {
         self.combat_log.clear();
-        self.skills.clear();
+        self.skills = SkillTable::default();
+        self.buffs = BuffTable::default();
+    }
+
+    /// Fight duration in milliseconds, from the squad combat start to the log end.
+    ///
+    /// Falls back to the time of the last event for logs without a `LogEnd`.
+    pub fn duration(&self) -> Option<u64> {
+        let start = self.metadata.log_start?;
+        let end = match self.metadata.log_end {
+            Some(end) => end.time,
+            None => self.combat_log.last()?.time,
+        };
+        Some(end.saturating_sub(start.time))
+    }
+
+    /// Converts an event `time` to milliseconds since the squad combat start.
+    ///
+    /// Events before the start, like the initial statechanges, are negative.
+    pub fn relative_ms(&self, time: u64) -> Option<i64> {
+        let start = self.metadata.log_start?;
+        Some(time as i64 - start.time as i64)
+    }
+
+    /// Converts an event `time` to wall-clock time, based on the server timestamp of the squad
+    /// combat start. Precise to the second at best.
+    pub fn wall_clock(&self, time: u64) -> Option<SystemTime> {
+        let start = self.metadata.log_start?.server_wall_clock();
+        let offset = self.relative_ms(time)?;
+        let delta = Duration::from_millis(offset.unsigned_abs());
+        if offset < 0 {
+            start.checked_sub(delta)
+        } else {
+            start.checked_add(delta)
+        }
+    }
+
+    /// Resolves an address or instance ID to the agent it referred to at `time`.
+    ///
+    /// Instance IDs are reused during a fight, so they are only matched against agents whose
+    /// aware window contains `time`. Instance ID 0 means "no agent" and never resolves. Addresses
+    /// are unique and always resolve.
+    pub fn agent_for(&self, id: impl Into<AgentId>, time: u64) -> Option<&Agent> {
+        match id.into() {
+            AgentId::Addr(addr) => self.agent_by_addr(addr),
+            AgentId::InstId(0) => None,
+            AgentId::InstId(instid) => self
+                .agents
+                .iter()
+                .find(|a| a.instid == instid && a.is_aware_at(time)),
+        }
+    }
+
+    /// The direct master of `agent`, see [`Agent::master`].
+    pub fn master_of(&self, agent: &Agent) -> Option<&Agent> {
+        agent.master.and_then(|addr| self.agent_by_addr(addr))
+    }
+
+    /// Looks up an agent by address, falling back to a scan if `agents` was modified.
+    fn agent_by_addr(&self, addr: u64) -> Option<&Agent> {
+        match self.by_addr.get(&addr).and_then(|&i| self.agents.get(i)) {
+            Some(agent) if agent.addr == addr => Some(agent),
+            _ => self.agents.iter().find(|a| a.addr == addr),
+        }
+    }
+
+    /// Follows the master links of `agent` to the top, so that damage and buffs of minions can be
+    /// credited to the owning player. Returns `agent` itself if it has no master.
+    pub fn root_master<'a>(&'a self, mut agent: &'a Agent) -> &'a Agent {
+        // bounded in case a broken log contains a cycle
+        for _ in 0..self.agents.len() {
+            match self.master_of(agent) {
+                Some(master) => agent = master,
+                None => break,
+            }
+        }
+        agent
+    }
+
+    /// All agents that are owned by `master`, directly or through a chain of masters.
+    pub fn minions_of<'a>(&'a self, master: &'a Agent) -> impl Iterator<Item = &'a Agent> {
+        self.agents.iter().filter(move |a| {
+            let mut agent = *a;
+            for _ in 0..self.agents.len() {
+                match self.master_of(agent) {
+                    Some(m) if m.addr == master.addr => return true,
+                    Some(m) => agent = m,
+                    None => return false,
+                }
+            }
+            false
+        })
+    }
+
+    /// The player agents, in agent table order.
+    pub fn players(&self) -> impl Iterator<Item = &Agent> {
+        self.agents.iter().filter(|a| a.is_player())
+    }
+
+    /// The boss agents of the encounter, in agent table order.
+    ///
+    /// Includes every agent whose species belongs to the encounter of [`Header::boss_id`] or of a
+    /// boss that `LogNpcUpdate` switched to, see [`crate::bossdata::Encounter::bosses`], as well
+    /// as the agents named by `LogNpcUpdate` themselves.
+    pub fn boss_agents(&self) -> Vec<&Agent> {
+        let mut species = vec![self.header.boss_id];
+        let mut addrs = Vec::new();
+        for evt in &self.combat_log {
+            if evt.statechange() == CbtStateChange::LogNpcUpdate {
+                species.push(evt.src_agent as u16);
+                addrs.push(evt.dst_agent);
+            }
+        }
+        let encounter_species: Vec<u16> = species
+            .iter()
+            .filter_map(|&id| BossId::from_header_id(id).encounter())
+            .flat_map(|encounter| encounter.bosses())
+            .map(|&boss| boss as u16)
+            .collect();
+        self.agents
+            .iter()
+            .filter(|a| {
+                addrs.contains(&a.addr)
+                    || a.species_id()
+                        .is_some_and(|id| species.contains(&id) || encounter_species.contains(&id))
+            })
+            .collect()
+    }
+
+    /// Iterates over the combat log, decoding every event.
+    pub fn events(&self) -> impl Iterator<Item = (&CbtEvent, Event)> {
+        self.combat_log.iter().map(|evt| (evt, evt.decode()))
     }
 }
 
@@ -203,12 +764,14 @@ impl std::fmt::Debug for Encounter {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Encounter {{ header: {:?}, agents: {:?}, skills: Vec({}), combat_log: Vec({}), pov: {:?} }}",
+            "Encounter {{ header: {:?}, agents: {:?}, skills: SkillTable({}), buffs: BuffTable({}), combat_log: Vec({}), pov: {:?}, metadata: {:?} }}",
             self.header,
             self.agents,
             self.skills.len(),
+            self.buffs.len(),
             self.combat_log.len(),
-            self.pov
+            self.pov,
+            self.metadata
         )
     }
 }
@@ -216,75 +779,95 @@ impl std::fmt::Debug for RawEncounter {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Encounter {{ header: {:?}, agents: {:?}, skills: Vec({}), combat_log: Vec({}), pov: {:?} }}",
+            "Encounter {{ header: {:?}, agents: {:?}, skills: Vec({}), combat_log: Vec({}), pov: {:?}, metadata: {:?} }}",
             self.header,
             self.agents,
             self.skills.len(),
             self.combat_log.len(),
-            self.pov
+            self.pov,
+            self.metadata
         )
     }
 }
-pub fn read_encounter(rdr: &mut impl Read) -> io::Result<Encounter> {
+pub fn read_encounter(rdr: &mut impl Read) -> Result<Encounter> {
+    let rdr = &mut CountingReader::new(rdr);
+
     // Read header
     let header = read_header(rdr)?;
 
     // Read agent count
-    let agent_count = rdr.read_u32::<LittleEndian>()?;
+    let agent_count = read_count(rdr, Section::AgentTable)?;
 
     // Read agent data
     let agents = read_agents(rdr, agent_count)?;
 
     // Read skill count
-    let skill_count = rdr.read_u32::<LittleEndian>()?;
+    let skill_count = read_count(rdr, Section::SkillTable)?;
 
     // Read skill data
     let skills = read_skills(rdr, skill_count)?;
 
     // Read combat log
-    let combat_log = read_log(rdr)?;
+    let combat_log = read_log(rdr, header.revision)?;
+
+    // Set instance IDs, aware times and masters
+    let mut agents = agents;
+    set_aware_times(&mut agents, &combat_log);
+    set_masters(&mut agents, &combat_log);
 
-    // Find pov
-    let pov = find_pov(combat_log.as_slice(), agents.as_slice());
+    // Find pov and metadata
+    let (pov, metadata) = find_metadata(combat_log.as_slice(), agents.as_slice());
+
+    // Attach skill info and timings
+    let mut skills: SkillTable = skills.iter().collect();
+    skills.add_definitions(&combat_log);
+
+    let by_addr = agents
+        .iter()
+        .enumerate()
+        .map(|(i, a)| (a.addr, i))
+        .collect();
 
     Ok(Encounter {
         header,
         agents,
         skills,
+        buffs: combat_log.iter().collect(),
         combat_log,
         pov,
+        metadata,
+        by_addr,
     })
 }
 
-pub fn read_encounter_raw(rdr: &mut impl Read) -> io::Result<RawEncounter> {
+pub fn read_encounter_raw(rdr: &mut impl Read) -> Result<RawEncounter> {
+    let rdr = &mut CountingReader::new(rdr);
+
     // Read header
     let header = read_header(rdr)?;
 
     // Read agent count
-    let agent_count = rdr.read_u32::<LittleEndian>()?;
+    let agent_count = read_count(rdr, Section::AgentTable)?;
 
     // Read agent data
     let agents_raw = read_agents_raw(rdr, agent_count)?;
-    let agents = agents_raw
-        .iter()
-        .filter_map(|a| {
-            (a.is_elite != 0xFFFFFFFF)
-                .then(|| a.try_into().ok())
-                .flatten()
-        })
-        .collect::<Vec<Agent>>();
+    let mut agents = agents_raw.iter().map(Agent::from).collect::<Vec<_>>();
 
     // Read skill count
-    let skill_count = rdr.read_u32::<LittleEndian>()?;
+    let skill_count = read_count(rdr, Section::SkillTable)?;
 
     // Read skill data
     let skills = read_skills(rdr, skill_count)?;
 
     // Read combat log
-    let combat_log = read_log(rdr)?;
+    let combat_log = read_log(rdr, header.revision)?;
 
-    // Find pov
-    let pov = find_pov(combat_log.as_slice(), agents.as_slice());
+    // Set instance IDs, aware times and masters
+    set_aware_times(&mut agents, &combat_log);
+    set_masters(&mut agents, &combat_log);
+
+    // Find pov and metadata
+    let (pov, metadata) = find_metadata(combat_log.as_slice(), agents.as_slice());
 
     Ok(RawEncounter {
         header,
@@ -292,22 +875,193 @@ pub fn read_encounter_raw(rdr: &mut impl Read) -> io::Result<RawEncounter> {
         skills,
         combat_log,
         pov,
+        metadata,
     })
 }
 
-fn find_pov(evts: &[CbtEvent], agents: &[Agent]) -> Option<Agent> {
+/// Reads the header, agent and skill tables and returns a stream over the remaining combat log.
+pub fn read_encounter_streaming<R: Read>(mut rdr: R) -> Result<StreamingEncounter<R>> {
+    let counting = &mut CountingReader::new(&mut rdr);
+
+    // Read header
+    let header = read_header(counting)?;
+
+    // Read agent count
+    let agent_count = read_count(counting, Section::AgentTable)?;
+
+    // Read agent data
+    let agents = read_agents(counting, agent_count)?;
+
+    // Read skill count
+    let skill_count = read_count(counting, Section::SkillTable)?;
+
+    // Read skill data
+    let skills = read_skills(counting, skill_count)?;
+
+    let offset = counting.count;
+    let revision = header.revision;
+    Ok(StreamingEncounter {
+        header,
+        agents,
+        skills: skills.iter().collect(),
+        events: EventStream::at_offset(rdr, offset, revision),
+    })
+}
+
+/// Reads only as much of the log as is needed to fill an [`EncounterSummary`].
+///
+/// Decoding stops as soon as the pov, map ID, game build and log start are known. If one of them
+/// is missing from the log, the whole combat log is scanned. Metadata statechanges that come after
+/// that point are not included in [`EncounterSummary::metadata`].
+pub fn read_summary(rdr: &mut impl Read) -> Result<EncounterSummary> {
+    let StreamingEncounter {
+        header,
+        agents,
+        mut events,
+        ..
+    } = read_encounter_streaming(rdr)?;
+
+    let mut pov = None;
+    let mut metadata = EncounterMetadata::default();
+
+    for evt in events.by_ref() {
+        let evt = evt?;
+        match evt.statechange() {
+            CbtStateChange::PointOfView => {
+                pov = Some(agents.iter().find(|a| a.addr == evt.src_agent).cloned())
+            }
+            _ => metadata.update(&evt),
+        }
+        if pov.is_some()
+            && metadata.map_id.is_some()
+            && metadata.gw_build.is_some()
+            && metadata.log_start.is_some()
+        {
+            break;
+        }
+    }
+
+    Ok(EncounterSummary {
+        header,
+        agents,
+        pov: pov.flatten(),
+        metadata,
+        evtc_bytes_read: events.offset,
+    })
+}
+
+/// Keeps track of the current byte offset for error reporting.
+struct CountingReader<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R> CountingReader<R> {
+    fn new(inner: R) -> Self {
+        Self { inner, count: 0 }
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+}
+
+/// Sets the instance ID and aware window of every agent that appears in the combat log.
+fn set_aware_times(agents: &mut [Agent], evts: &[CbtEvent]) {
+    let by_addr: HashMap<u64, usize> = agents
+        .iter()
+        .enumerate()
+        .map(|(i, a)| (a.addr, i))
+        .collect();
+    let mut update = |addr: u64, instid: u16, time: u64| {
+        let Some(&i) = by_addr.get(&addr) else {
+            return;
+        };
+        let agent = &mut agents[i];
+        let first = agent.aware.map_or(time, |(first, _)| first);
+        agent.aware = Some((first, time));
+        if instid != 0 {
+            agent.instid = instid;
+        }
+    };
     for evt in evts {
-        if evt.is_statechange == CbtStateChange::PointOfView as u32 as u8 {
-            return agents.iter().find(|a| a.addr == evt.src_agent).cloned();
+        let statechange = evt.statechange();
+        if statechange == CbtStateChange::None {
+            update(evt.src_agent, evt.src_instid, evt.time);
+            update(evt.dst_agent, evt.dst_instid, evt.time);
+        } else if statechange.src_is_agent() {
+            update(evt.src_agent, evt.src_instid, evt.time);
         }
     }
-    None
 }
-#[repr(u32)] // ensures the enum is represented as a 32-bit unsigned integer
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+
+/// Links minions to their masters using the master instance IDs of the combat events.
+///
+/// Needs the aware times from [`set_aware_times`], since the master instance ID has to be resolved
+/// to the agent that had it at the time of the event.
+/// Each minion keeps the first master it resolves to, see [`Agent::master`].
+fn set_masters(agents: &mut [Agent], evts: &[CbtEvent]) {
+    let by_addr: HashMap<u64, usize> = agents
+        .iter()
+        .enumerate()
+        .map(|(i, a)| (a.addr, i))
+        .collect();
+    let mut by_instid: HashMap<u16, Vec<usize>> = HashMap::new();
+    for (i, agent) in agents.iter().enumerate() {
+        by_instid.entry(agent.instid).or_default().push(i);
+    }
+    for evt in evts {
+        if evt.statechange() != CbtStateChange::None {
+            continue;
+        }
+        for (addr, master_instid) in [
+            (evt.src_agent, evt.src_master_instid),
+            (evt.dst_agent, evt.dst_master_instid),
+        ] {
+            if master_instid == 0 {
+                continue;
+            }
+            let Some(&minion) = by_addr.get(&addr) else {
+                continue;
+            };
+            if agents[minion].master.is_some() {
+                continue;
+            }
+            let master = by_instid.get(&master_instid).and_then(|candidates| {
+                candidates
+                    .iter()
+                    .map(|&i| &agents[i])
+                    .find(|a| a.addr != addr && a.is_aware_at(evt.time))
+            });
+            agents[minion].master = master.map(|a| a.addr);
+        }
+    }
+}
+
+/// Finds the pov and collects the [`EncounterMetadata`] in a single pass over the combat log.
+fn find_metadata(evts: &[CbtEvent], agents: &[Agent]) -> (Option<Agent>, EncounterMetadata) {
+    let mut pov = None;
+    let mut metadata = EncounterMetadata::default();
+    for evt in evts {
+        if evt.statechange() == CbtStateChange::PointOfView {
+            pov = pov.or_else(|| Some(agents.iter().find(|a| a.addr == evt.src_agent).cloned()));
+        } else {
+            metadata.update(evt);
+        }
+    }
+    (pov.flatten(), metadata)
+}
+/// Kind of statechange, from the `is_statechange` byte of a [`CbtEvent`].
+///
+/// Use [`CbtStateChange::from_u8`] or [`CbtEvent::statechange`] to convert the raw byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CbtStateChange {
     /// Not used - not this kind of event
-    None = 0,
+    None,
     /// Agent entered combat
     ///
     /// - `src_agent`: relates to agent
@@ -541,7 +1295,7 @@ pub enum CbtStateChange {
     /// Agent breakbar percentage changed
     ///
     /// - `src_agent`: relates to agent
-    /// - `dst_agent`: breakbar percent * 10000
+    /// - `value`: (float*)&value, breakbar as a fraction from 0 to 1
     /// - `evtc`: limited to agent table outside instances
     /// - `realtime`: limited to squad
     BreakbarPercent,
@@ -596,12 +1350,381 @@ pub enum CbtStateChange {
     Glider,
     /// Disable stopped early
     StunBreak,
-    /// Unknown/unsupported type newer than this list
-    Unknown,
+    /// Unknown/unsupported type newer than this list, with the raw value
+    Unknown(u8),
+}
+
+impl CbtStateChange {
+    /// Converts the raw `is_statechange` byte.
+    pub fn from_u8(raw: u8) -> Self {
+        use CbtStateChange::*;
+        match raw {
+            0 => None,
+            1 => EnterCombat,
+            2 => ExitCombat,
+            3 => ChangeUp,
+            4 => ChangeDead,
+            5 => ChangeDown,
+            6 => Spawn,
+            7 => Despawn,
+            8 => HealthPctUpdate,
+            9 => SqCombatStart,
+            10 => LogEnd,
+            11 => WeapSwap,
+            12 => MaxHealthUpdate,
+            13 => PointOfView,
+            14 => Language,
+            15 => GwBuild,
+            16 => ShardId,
+            17 => Reward,
+            18 => BuffInitial,
+            19 => Position,
+            20 => Velocity,
+            21 => Facing,
+            22 => TeamChange,
+            23 => AttackTarget,
+            24 => Targetable,
+            25 => MapId,
+            26 => ReplInfo,
+            27 => StackActive,
+            28 => StackReset,
+            29 => Guild,
+            30 => BuffInfo,
+            31 => BuffFormula,
+            32 => SkillInfo,
+            33 => SkillTiming,
+            34 => BreakbarState,
+            35 => BreakbarPercent,
+            36 => Integrity,
+            37 => Marker,
+            38 => BarrierPctUpdate,
+            39 => StatReset,
+            40 => Extension,
+            41 => ApiDelayed,
+            42 => InstanceStart,
+            43 => RateHealth,
+            44 => Last90BeforeDown,
+            45 => Effect,
+            46 => IdToGuid,
+            47 => LogNpcUpdate,
+            48 => IdleEvent,
+            49 => ExtensionCombat,
+            50 => FractalScale,
+            51 => Effect2,
+            52 => Ruleset,
+            53 => SquadMarker,
+            54 => ArcBuild,
+            55 => Glider,
+            56 => StunBreak,
+            _ => Unknown(raw),
+        }
+    }
+
+    /// Whether `src_agent` holds an agent address for this statechange.
+    ///
+    /// Statechanges like [`CbtStateChange::MapId`] use `src_agent` for their payload instead.
+    pub fn src_is_agent(self) -> bool {
+        use CbtStateChange::*;
+        matches!(
+            self,
+            EnterCombat
+                | ExitCombat
+                | ChangeUp
+                | ChangeDead
+                | ChangeDown
+                | Spawn
+                | Despawn
+                | HealthPctUpdate
+                | WeapSwap
+                | MaxHealthUpdate
+                | PointOfView
+                | BuffInitial
+                | Position
+                | Velocity
+                | Facing
+                | TeamChange
+                | AttackTarget
+                | Targetable
+                | StackActive
+                | StackReset
+                | Guild
+                | BreakbarState
+                | BreakbarPercent
+                | Marker
+                | BarrierPctUpdate
+                | Glider
+                | StunBreak
+        )
+    }
+
+    /// The raw `is_statechange` byte.
+    pub fn as_u8(self) -> u8 {
+        use CbtStateChange::*;
+        match self {
+            None => 0,
+            EnterCombat => 1,
+            ExitCombat => 2,
+            ChangeUp => 3,
+            ChangeDead => 4,
+            ChangeDown => 5,
+            Spawn => 6,
+            Despawn => 7,
+            HealthPctUpdate => 8,
+            SqCombatStart => 9,
+            LogEnd => 10,
+            WeapSwap => 11,
+            MaxHealthUpdate => 12,
+            PointOfView => 13,
+            Language => 14,
+            GwBuild => 15,
+            ShardId => 16,
+            Reward => 17,
+            BuffInitial => 18,
+            Position => 19,
+            Velocity => 20,
+            Facing => 21,
+            TeamChange => 22,
+            AttackTarget => 23,
+            Targetable => 24,
+            MapId => 25,
+            ReplInfo => 26,
+            StackActive => 27,
+            StackReset => 28,
+            Guild => 29,
+            BuffInfo => 30,
+            BuffFormula => 31,
+            SkillInfo => 32,
+            SkillTiming => 33,
+            BreakbarState => 34,
+            BreakbarPercent => 35,
+            Integrity => 36,
+            Marker => 37,
+            BarrierPctUpdate => 38,
+            StatReset => 39,
+            Extension => 40,
+            ApiDelayed => 41,
+            InstanceStart => 42,
+            RateHealth => 43,
+            Last90BeforeDown => 44,
+            Effect => 45,
+            IdToGuid => 46,
+            LogNpcUpdate => 47,
+            IdleEvent => 48,
+            ExtensionCombat => 49,
+            FractalScale => 50,
+            Effect2 => 51,
+            Ruleset => 52,
+            SquadMarker => 53,
+            ArcBuild => 54,
+            Glider => 55,
+            StunBreak => 56,
+            Unknown(raw) => raw,
+        }
+    }
+}
+
+impl From<u8> for CbtStateChange {
+    fn from(raw: u8) -> Self {
+        Self::from_u8(raw)
+    }
+}
+
+impl From<CbtStateChange> for u8 {
+    fn from(statechange: CbtStateChange) -> Self {
+        statechange.as_u8()
+    }
+}
+
+/// Friend or foe, from the `iff` byte of a [`CbtEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Iff {
+    Friend,
+    Foe,
+    /// arcdps could not tell (raw value 2) or a value newer than this list
+    Unknown(u8),
+}
+
+impl Iff {
+    pub fn from_u8(raw: u8) -> Self {
+        match raw {
+            0 => Iff::Friend,
+            1 => Iff::Foe,
+            _ => Iff::Unknown(raw),
+        }
+    }
+
+    pub fn as_u8(self) -> u8 {
+        match self {
+            Iff::Friend => 0,
+            Iff::Foe => 1,
+            Iff::Unknown(raw) => raw,
+        }
+    }
+}
+
+/// Result of a direct damage event, from the `result` byte of a [`CbtEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CbtResult {
+    /// Good physical hit
+    Normal,
+    /// Physical hit was a critical hit
+    Crit,
+    /// Physical hit was a glance
+    Glance,
+    /// Physical hit was blocked, e.g. by Mesmer shield 4
+    Block,
+    /// Physical hit was evaded, e.g. by a dodge or Mesmer sword 2
+    Evade,
+    /// Physical hit interrupted something
+    Interrupt,
+    /// Physical hit was "invulned" or absorbed, e.g. by Guardian elite
+    Absorb,
+    /// Physical hit missed
+    Blind,
+    /// Source hit target if damaging buff
+    KillingBlow,
+    /// Source hit target if damaging buff
+    Downed,
+    /// Source hit target if damaging buff, value is breakbar damage
+    Breakbar,
+    /// On-activation event, not damage
+    Activation,
+    /// Source hit target with a crowd control effect
+    CrowdControl,
+    /// Unknown result newer than this list
+    Unknown(u8),
+}
+
+impl CbtResult {
+    pub fn from_u8(raw: u8) -> Self {
+        use CbtResult::*;
+        match raw {
+            0 => Normal,
+            1 => Crit,
+            2 => Glance,
+            3 => Block,
+            4 => Evade,
+            5 => Interrupt,
+            6 => Absorb,
+            7 => Blind,
+            8 => KillingBlow,
+            9 => Downed,
+            10 => Breakbar,
+            11 => Activation,
+            12 => CrowdControl,
+            _ => Unknown(raw),
+        }
+    }
+
+    pub fn as_u8(self) -> u8 {
+        use CbtResult::*;
+        match self {
+            Normal => 0,
+            Crit => 1,
+            Glance => 2,
+            Block => 3,
+            Evade => 4,
+            Interrupt => 5,
+            Absorb => 6,
+            Blind => 7,
+            KillingBlow => 8,
+            Downed => 9,
+            Breakbar => 10,
+            Activation => 11,
+            CrowdControl => 12,
+            Unknown(raw) => raw,
+        }
+    }
+}
+
+/// Kind of skill activation, from the `is_activation` byte of a [`CbtEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Activation {
+    /// Not used - not this kind of event
+    None,
+    /// Started skill activation without quickness
+    Start,
+    /// Unused, previously started skill activation with quickness
+    QuicknessUnused,
+    /// Stopped skill activation with reaching tooltip time
+    CancelFire,
+    /// Stopped skill activation without reaching tooltip time
+    CancelCancel,
+    /// Animation completed fully
+    Reset,
+    /// Unknown activation newer than this list
+    Unknown(u8),
+}
+
+impl Activation {
+    pub fn from_u8(raw: u8) -> Self {
+        use Activation::*;
+        match raw {
+            0 => None,
+            1 => Start,
+            2 => QuicknessUnused,
+            3 => CancelFire,
+            4 => CancelCancel,
+            5 => Reset,
+            _ => Unknown(raw),
+        }
+    }
+
+    pub fn as_u8(self) -> u8 {
+        use Activation::*;
+        match self {
+            None => 0,
+            Start => 1,
+            QuicknessUnused => 2,
+            CancelFire => 3,
+            CancelCancel => 4,
+            Reset => 5,
+            Unknown(raw) => raw,
+        }
+    }
+}
+
+/// Kind of buff removal, from the `is_buffremove` byte of a [`CbtEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuffRemove {
+    /// Not used - not this kind of event
+    None,
+    /// Last or all stacks removed, sent by server
+    All,
+    /// Single stack removed, sent by server
+    Single,
+    /// Single stack removed, automatically by arcdps on ooc or all stack
+    Manual,
+    /// Unknown removal newer than this list
+    Unknown(u8),
+}
+
+impl BuffRemove {
+    pub fn from_u8(raw: u8) -> Self {
+        use BuffRemove::*;
+        match raw {
+            0 => None,
+            1 => All,
+            2 => Single,
+            3 => Manual,
+            _ => Unknown(raw),
+        }
+    }
+
+    pub fn as_u8(self) -> u8 {
+        use BuffRemove::*;
+        match self {
+            None => 0,
+            All => 1,
+            Single => 2,
+            Manual => 3,
+            Unknown(raw) => raw,
+        }
+    }
 }
+
 /// Represents a combat event.
-#[repr(C, packed)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CbtEvent {
     /// Time of event, retrieved using `timegettime()`.
     pub time: u64,
@@ -655,3 +1778,140 @@ pub struct CbtEvent {
     pub pad63: u8,
     pub pad64: u8,
 }
+
+impl CbtEvent {
+    /// The decoded `is_statechange` byte.
+    pub fn statechange(&self) -> CbtStateChange {
+        CbtStateChange::from_u8(self.is_statechange)
+    }
+
+    /// The decoded `iff` byte.
+    pub fn iff(&self) -> Iff {
+        Iff::from_u8(self.iff)
+    }
+
+    /// The decoded `result` byte.
+    pub fn result(&self) -> CbtResult {
+        CbtResult::from_u8(self.result)
+    }
+
+    /// The decoded `is_activation` byte.
+    pub fn activation(&self) -> Activation {
+        Activation::from_u8(self.is_activation)
+    }
+
+    /// The decoded `is_buffremove` byte.
+    pub fn buffremove(&self) -> BuffRemove {
+        BuffRemove::from_u8(self.is_buffremove)
+    }
+
+    /// Size of an event in the combat log.
+    pub const SIZE: usize = 64;
+
+    /// Decodes a revision 1 combat event.
+    pub fn from_bytes(buf: &[u8; Self::SIZE]) -> Self {
+        Self {
+            time: LittleEndian::read_u64(&buf[0..8]),
+            src_agent: LittleEndian::read_u64(&buf[8..16]),
+            dst_agent: LittleEndian::read_u64(&buf[16..24]),
+            value: LittleEndian::read_i32(&buf[24..28]),
+            buff_dmg: LittleEndian::read_i32(&buf[28..32]),
+            overstack_value: LittleEndian::read_u32(&buf[32..36]),
+            skillid: LittleEndian::read_u32(&buf[36..40]),
+            src_instid: LittleEndian::read_u16(&buf[40..42]),
+            dst_instid: LittleEndian::read_u16(&buf[42..44]),
+            src_master_instid: LittleEndian::read_u16(&buf[44..46]),
+            dst_master_instid: LittleEndian::read_u16(&buf[46..48]),
+            iff: buf[48],
+            buff: buf[49],
+            result: buf[50],
+            is_activation: buf[51],
+            is_buffremove: buf[52],
+            is_ninety: buf[53],
+            is_fifty: buf[54],
+            is_moving: buf[55],
+            is_statechange: buf[56],
+            is_flanking: buf[57],
+            is_shields: buf[58],
+            is_offcycle: buf[59],
+            pad61: buf[60],
+            pad62: buf[61],
+            pad63: buf[62],
+            pad64: buf[63],
+        }
+    }
+
+    /// Decodes a revision 0 combat event.
+    ///
+    /// Revision 0 has 16 bit `overstack_value` and `skillid` fields and no `dst_master_instid`,
+    /// which is set to 0. The unused bytes after `src_master_instid` are skipped and the flags
+    /// are moved to their revision 1 positions.
+    pub fn from_bytes_rev0(buf: &[u8; Self::SIZE]) -> Self {
+        Self {
+            time: LittleEndian::read_u64(&buf[0..8]),
+            src_agent: LittleEndian::read_u64(&buf[8..16]),
+            dst_agent: LittleEndian::read_u64(&buf[16..24]),
+            value: LittleEndian::read_i32(&buf[24..28]),
+            buff_dmg: LittleEndian::read_i32(&buf[28..32]),
+            overstack_value: LittleEndian::read_u16(&buf[32..34]).into(),
+            skillid: LittleEndian::read_u16(&buf[34..36]).into(),
+            src_instid: LittleEndian::read_u16(&buf[36..38]),
+            dst_instid: LittleEndian::read_u16(&buf[38..40]),
+            src_master_instid: LittleEndian::read_u16(&buf[40..42]),
+            dst_master_instid: 0,
+            // 42..51 are internal offsets that arcdps used to write
+            iff: buf[51],
+            buff: buf[52],
+            result: buf[53],
+            is_activation: buf[54],
+            is_buffremove: buf[55],
+            is_ninety: buf[56],
+            is_fifty: buf[57],
+            is_moving: buf[58],
+            is_statechange: buf[59],
+            is_flanking: buf[60],
+            is_shields: buf[61],
+            is_offcycle: buf[62],
+            pad61: 0,
+            pad62: 0,
+            pad63: 0,
+            pad64: 0,
+        }
+    }
+}
+
+/// State of a defiance bar, from the `BreakbarState` statechange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BreakbarState {
+    /// The breakbar can be damaged
+    Active,
+    /// The breakbar was broken and is refilling
+    Recover,
+    /// The breakbar is immune to crowd control
+    Immune,
+    /// The agent has no breakbar
+    None,
+    Unknown(u64),
+}
+
+impl BreakbarState {
+    pub fn from_u64(raw: u64) -> Self {
+        match raw {
+            0 => BreakbarState::Active,
+            1 => BreakbarState::Recover,
+            2 => BreakbarState::Immune,
+            3 => BreakbarState::None,
+            _ => BreakbarState::Unknown(raw),
+        }
+    }
+
+    pub fn as_u64(self) -> u64 {
+        match self {
+            BreakbarState::Active => 0,
+            BreakbarState::Recover => 1,
+            BreakbarState::Immune => 2,
+            BreakbarState::None => 3,
+            BreakbarState::Unknown(raw) => raw,
+        }
+    }
+}
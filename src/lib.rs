@@ -1,30 +1,103 @@
-use std::io::BufReader;
+use std::io::{BufRead, BufReader, Cursor, Read, Seek};
 use std::path::Path;
 use zip::read::ZipArchive;
 
 pub mod bossdata;
+pub mod buff;
+mod difficulty;
+mod error;
+pub mod event;
 pub mod evtc;
+pub mod outcome;
+pub mod skill;
+pub mod track;
 
-pub fn open(path: impl AsRef<Path>) -> anyhow::Result<evtc::Encounter> {
-    let file = std::fs::File::open(&path)?;
-    let reader = BufReader::new(file);
-    let mut zip = ZipArchive::new(reader)?;
-    if zip.len() == 0 {
-        anyhow::bail!("Empty zip file");
-    }
-    let z = zip.by_index(0)?;
-    let mut file = BufReader::new(z);
-    Ok(evtc::read_encounter(&mut file)?)
+pub use error::{Error, Result, Section};
+
+pub fn open(path: impl AsRef<Path>) -> Result<evtc::Encounter> {
+    from_reader(std::fs::File::open(&path)?)
+}
+
+pub fn open_raw(path: impl AsRef<Path>) -> Result<evtc::RawEncounter> {
+    from_reader_raw(std::fs::File::open(&path)?)
+}
+
+/// Parses a zipped or plain evtc log from any seekable reader.
+pub fn from_reader(reader: impl Read + Seek) -> Result<evtc::Encounter> {
+    read_container(BufReader::new(reader), |mut rdr| {
+        evtc::read_encounter(&mut rdr)
+    })
+}
+
+pub fn from_reader_raw(reader: impl Read + Seek) -> Result<evtc::RawEncounter> {
+    read_container(BufReader::new(reader), |mut rdr| {
+        evtc::read_encounter_raw(&mut rdr)
+    })
+}
+
+/// Parses a zipped or plain evtc log that is already in memory, e.g. an upload body.
+pub fn from_slice(bytes: &[u8]) -> Result<evtc::Encounter> {
+    read_container(Cursor::new(bytes), |mut rdr| evtc::read_encounter(&mut rdr))
+}
+
+pub fn from_slice_raw(bytes: &[u8]) -> Result<evtc::RawEncounter> {
+    read_container(Cursor::new(bytes), |mut rdr| {
+        evtc::read_encounter_raw(&mut rdr)
+    })
+}
+
+/// Opens a log and only reads the metadata at its start, skipping the rest of the combat log.
+pub fn open_metadata(path: impl AsRef<Path>) -> Result<evtc::EncounterSummary> {
+    from_reader_metadata(std::fs::File::open(&path)?)
+}
+
+pub fn from_reader_metadata(reader: impl Read + Seek) -> Result<evtc::EncounterSummary> {
+    read_container(BufReader::new(reader), |mut rdr| {
+        evtc::read_summary(&mut rdr)
+    })
+}
+
+/// Opens a log and passes it to `f` with the combat log still unread.
+///
+/// The events are decoded one at a time while iterating
+/// [`StreamingEncounter::events`][evtc::StreamingEncounter::events], so huge logs can be analyzed
+/// without loading them into memory.
+pub fn open_streaming<T>(
+    path: impl AsRef<Path>,
+    f: impl FnOnce(evtc::StreamingEncounter<&mut dyn Read>) -> T,
+) -> Result<T> {
+    from_reader_streaming(std::fs::File::open(&path)?, f)
+}
+
+pub fn from_reader_streaming<T>(
+    reader: impl Read + Seek,
+    f: impl FnOnce(evtc::StreamingEncounter<&mut dyn Read>) -> T,
+) -> Result<T> {
+    read_container(BufReader::new(reader), |rdr| {
+        Ok(f(evtc::read_encounter_streaming(rdr)?))
+    })
 }
 
-pub fn open_raw(path: impl AsRef<Path>) -> anyhow::Result<evtc::RawEncounter> {
-    let file = std::fs::File::open(&path)?;
-    let reader = BufReader::new(file);
-    let mut zip = ZipArchive::new(reader)?;
-    if zip.len() == 0 {
-        anyhow::bail!("Empty zip file");
+/// Sniffs the first bytes of `reader` and hands the evtc stream to `parse`,
+/// either directly for plain `.evtc` files or through the zip layer for
+/// `.zevtc`/`.evtc.zip` files.
+fn read_container<R, T>(mut reader: R, parse: impl FnOnce(&mut dyn Read) -> Result<T>) -> Result<T>
+where
+    R: BufRead + Seek,
+{
+    let magic = reader.fill_buf()?;
+    if magic.starts_with(b"EVTC") {
+        parse(&mut reader)
+    } else if magic.starts_with(b"PK") {
+        let mut zip = ZipArchive::new(reader)?;
+        // some tools put a directory entry in front of the log
+        let index = (0..zip.len())
+            .find(|&i| zip.by_index(i).is_ok_and(|f| f.is_file()))
+            .ok_or(Error::EmptyArchive)?;
+        let z = zip.by_index(index)?;
+        let mut file = BufReader::new(z);
+        parse(&mut file)
+    } else {
+        Err(Error::UnknownFormat)
     }
-    let z = zip.by_index(0)?;
-    let mut file = BufReader::new(z);
-    Ok(evtc::read_encounter_raw(&mut file)?)
 }
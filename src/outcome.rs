@@ -0,0 +1,153 @@
+//! Kill/fail detection for encounters.
+//!
+//! Most bosses count as killed once their agent dies, but some never do: they despawn, turn
+//! invulnerable or run away once beaten. For those, the lowest recorded health of the boss is
+//! checked instead, following the rules of the common log parsers. The wiggly box reward always
+//! counts as a success, but arcdps only records it for the first clear of the week.
+use std::collections::HashSet;
+
+use crate::bossdata::BossId;
+use crate::evtc::{CbtStateChange, Encounter};
+
+/// Whether an encounter was a success.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    /// The encounter was cleared.
+    Success,
+    /// The squad wiped or the log ended before the boss was beaten.
+    Failure,
+}
+
+impl Outcome {
+    fn from_bool(success: bool) -> Self {
+        if success {
+            Outcome::Success
+        } else {
+            Outcome::Failure
+        }
+    }
+}
+
+impl Encounter {
+    /// Determines whether the encounter was a success, based on [`BossId::from_header_id`].
+    ///
+    /// Returns `None` for logs that are not tied to a boss, e.g. WvW or instance logs.
+    pub fn outcome(&self) -> Option<Outcome> {
+        use BossId as BI;
+        let boss = BossId::from_header_id(self.header.boss_id);
+        let success = match boss {
+            BI::Unknown | BI::Wvw | BI::Instance => return None,
+            // Both twins have to die
+            BI::Nikare | BI::Kenut => self.has_reward() || self.all_dead(&[BI::Nikare, BI::Kenut]),
+            BI::Berg | BI::Zane | BI::Nurella => {
+                self.has_reward() || self.all_dead(&[BI::Berg, BI::Zane, BI::Nurella])
+            }
+            BI::TheVoice | BI::TheClaw => {
+                self.has_reward() || self.all_dead(&[BI::TheVoice, BI::TheClaw])
+            }
+            BI::CerusLonelyTower | BI::DeimosLonelyTower => {
+                self.has_reward() || self.all_dead(&[BI::CerusLonelyTower, BI::DeimosLonelyTower])
+            }
+            // The construct dies before Fraenir himself is fought
+            BI::Fraenir | BI::FraenirConstruct => {
+                self.has_reward() || self.any_dead(&[BI::Fraenir])
+            }
+            BI::PrototypeVermilion
+            | BI::PrototypeIndigo
+            | BI::PrototypeArsenite
+            | BI::PrototypeVermilionCm
+            | BI::PrototypeIndigoCm
+            | BI::PrototypeArseniteCm => {
+                self.has_reward()
+                    || self.all_dead(&[
+                        BI::PrototypeVermilion,
+                        BI::PrototypeIndigo,
+                        BI::PrototypeArsenite,
+                    ])
+                    || self.all_dead(&[
+                        BI::PrototypeVermilionCm,
+                        BI::PrototypeIndigoCm,
+                        BI::PrototypeArseniteCm,
+                    ])
+            }
+            // Killing either eye ends the encounter
+            BI::EyeOfJudgement | BI::EyeOfFate => {
+                self.has_reward() || self.any_dead(&[BI::EyeOfJudgement, BI::EyeOfFate])
+            }
+            // Despawns or turns invulnerable instead of dying, at close to no health
+            BI::ConjuredAmalgamate
+            | BI::Dhuum
+            | BI::McLeod
+            | BI::BrokenKing
+            | BI::SoulEater
+            | BI::CaptainMaiTrin
+            | BI::CaptainMaiTrin2
+            | BI::CaptainMaiTrin3
+            | BI::Ankka
+            | BI::MinisterLi
+            | BI::MinisterLiCm
+            | BI::Dagda => self.has_reward() || self.health_below(&[boss], 2.0),
+            BI::DragonVoid1 | BI::DragonVoid2 | BI::DragonVoid3 => {
+                self.has_reward()
+                    || self.health_below(&[BI::DragonVoid1, BI::DragonVoid2, BI::DragonVoid3], 2.0)
+            }
+            // Escort events where the boss agent has no health to lose, only the reward tells
+            BI::TwistedCastle | BI::River => self.has_reward(),
+            // Turns invulnerable instead of dying
+            BI::QadimThePeerless | BI::Artsariiv | BI::SorrowfulSpellcaster => {
+                self.has_reward() || self.any_dead(&[boss]) || self.health_below(&[boss], 2.0)
+            }
+            _ => self.has_reward() || self.any_dead(&[boss]),
+        };
+        Some(Outcome::from_bool(success))
+    }
+
+    /// Whether the log contains a wiggly box reward.
+    fn has_reward(&self) -> bool {
+        self.combat_log
+            .iter()
+            .any(|evt| evt.statechange() == CbtStateChange::Reward)
+    }
+
+    /// Addresses of the agents that died during the fight.
+    fn dead_agents(&self) -> HashSet<u64> {
+        self.combat_log
+            .iter()
+            .filter(|evt| evt.statechange() == CbtStateChange::ChangeDead)
+            .map(|evt| evt.src_agent)
+            .collect()
+    }
+
+    /// Whether an agent of every given boss died.
+    fn all_dead(&self, bosses: &[BossId]) -> bool {
+        let dead = self.dead_agents();
+        bosses.iter().all(|&boss| {
+            self.agents
+                .iter()
+                .any(|a| a.species_id() == Some(boss as u16) && dead.contains(&a.addr))
+        })
+    }
+
+    /// Whether an agent of any of the given bosses died.
+    fn any_dead(&self, bosses: &[BossId]) -> bool {
+        let dead = self.dead_agents();
+        self.agents.iter().any(|a| {
+            dead.contains(&a.addr) && bosses.iter().any(|&b| a.species_id() == Some(b as u16))
+        })
+    }
+
+    /// Whether the health of an agent of the given bosses dropped below `percent` at some point.
+    fn health_below(&self, bosses: &[BossId], percent: f32) -> bool {
+        let addrs: HashSet<u64> = self
+            .agents
+            .iter()
+            .filter(|a| bosses.iter().any(|&b| a.species_id() == Some(b as u16)))
+            .map(|a| a.addr)
+            .collect();
+        self.combat_log.iter().any(|evt| {
+            evt.statechange() == CbtStateChange::HealthPctUpdate
+                && addrs.contains(&evt.src_agent)
+                && (evt.dst_agent as f32 / 100.0) < percent
+        })
+    }
+}
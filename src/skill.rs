@@ -0,0 +1,221 @@
+//! Skill names from the skill table of a log.
+use std::collections::HashMap;
+
+use crate::event::Event;
+use crate::evtc::{CbtEvent, CbtStateChange, EvtcSkill};
+
+/// A skill from the skill table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Skill {
+    pub id: u32,
+    /// The name arcdps wrote, `None` if it left the name empty
+    pub name: Option<String>,
+    /// From the `SkillInfo` statechange, `None` if the log has none for this skill
+    pub info: Option<SkillInfo>,
+    /// From the `SkillTiming` statechanges, in log order
+    pub timings: Vec<SkillTiming>,
+}
+
+/// Cost, ranges and cast time of a skill, the payload of a `SkillInfo` statechange.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkillInfo {
+    pub cost: f32,
+    pub range0: f32,
+    pub range1: f32,
+    /// Cast time shown in the tooltip, in milliseconds
+    pub tooltip_time: f32,
+}
+
+impl SkillInfo {
+    pub(crate) fn from_event(evt: &CbtEvent) -> Self {
+        // (float*)&time is float[4], spanning time and src_agent
+        let float = |bits: u64| f32::from_bits(bits as u32);
+        Self {
+            cost: float(evt.time),
+            range0: float(evt.time >> 32),
+            range1: float(evt.src_agent),
+            tooltip_time: float(evt.src_agent >> 32),
+        }
+    }
+}
+
+/// An action marker of a skill, the payload of a `SkillTiming` statechange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkillTiming {
+    pub timing_type: u64,
+    /// Time since activation in milliseconds
+    pub at: u64,
+}
+
+impl From<&EvtcSkill> for Skill {
+    fn from(raw: &EvtcSkill) -> Self {
+        let name = raw.name().split(|&c| c == 0).next().unwrap_or_default();
+        Self {
+            id: raw.id() as u32,
+            name: (!name.is_empty()).then(|| String::from_utf8_lossy(name).into_owned()),
+            info: None,
+            timings: Vec::new(),
+        }
+    }
+}
+
+/// The skill table of a log, indexed by the `skillid` of [`CbtEvent`][crate::evtc::CbtEvent]s.
+#[derive(Debug, Clone, Default)]
+pub struct SkillTable {
+    skills: Vec<Skill>,
+    by_id: HashMap<u32, usize>,
+}
+
+impl SkillTable {
+    pub fn get(&self, id: u32) -> Option<&Skill> {
+        self.by_id.get(&id).map(|&i| &self.skills[i])
+    }
+
+    /// The name of a skill as written in the log.
+    pub fn name(&self, id: u32) -> Option<&str> {
+        self.get(id)?.name.as_deref()
+    }
+
+    /// The name of a skill, falling back to [`builtin_name`] for skills arcdps left unnamed or
+    /// did not include in the skill table.
+    pub fn name_or_builtin(&self, id: u32) -> Option<&str> {
+        self.name(id).or_else(|| builtin_name(id))
+    }
+
+    /// Iterates over the skills in skill table order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Skill> {
+        self.skills.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.skills.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.skills.is_empty()
+    }
+
+    /// Attaches the `SkillInfo` and `SkillTiming` statechanges to their skills.
+    ///
+    /// Skills that are missing from the skill table are added without a name.
+    pub(crate) fn add_definitions<'a>(&mut self, evts: impl IntoIterator<Item = &'a CbtEvent>) {
+        for evt in evts {
+            if !matches!(
+                evt.statechange(),
+                CbtStateChange::SkillInfo | CbtStateChange::SkillTiming
+            ) {
+                continue;
+            }
+            match evt.decode() {
+                Event::SkillInfo { skill_id, info } => {
+                    self.entry(skill_id).info.get_or_insert(info);
+                }
+                Event::SkillTiming {
+                    skill_id,
+                    timing_type,
+                    at,
+                } => self
+                    .entry(skill_id)
+                    .timings
+                    .push(SkillTiming { timing_type, at }),
+                _ => {}
+            }
+        }
+    }
+
+    fn entry(&mut self, id: u32) -> &mut Skill {
+        let index = *self.by_id.entry(id).or_insert_with(|| {
+            self.skills.push(Skill {
+                id,
+                name: None,
+                info: None,
+                timings: Vec::new(),
+            });
+            self.skills.len() - 1
+        });
+        &mut self.skills[index]
+    }
+}
+
+impl<'a> FromIterator<&'a EvtcSkill> for SkillTable {
+    fn from_iter<I: IntoIterator<Item = &'a EvtcSkill>>(iter: I) -> Self {
+        iter.into_iter().map(Skill::from).collect()
+    }
+}
+
+impl FromIterator<Skill> for SkillTable {
+    fn from_iter<I: IntoIterator<Item = Skill>>(iter: I) -> Self {
+        let skills: Vec<Skill> = iter.into_iter().collect();
+        let by_id = skills.iter().enumerate().map(|(i, s)| (s.id, i)).collect();
+        Self { skills, by_id }
+    }
+}
+
+impl<'a> IntoIterator for &'a SkillTable {
+    type Item = &'a Skill;
+    type IntoIter = std::slice::Iter<'a, Skill>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Well-known skills and buffs, sorted by ID.
+const BUILTIN_NAMES: &[(u32, &str)] = &[
+    (717, "Protection"),
+    (718, "Regeneration"),
+    (719, "Swiftness"),
+    (720, "Blinded"),
+    (721, "Crippled"),
+    (722, "Chilled"),
+    (723, "Poisoned"),
+    (725, "Fury"),
+    (726, "Vigor"),
+    (727, "Immobile"),
+    (736, "Bleeding"),
+    (737, "Burning"),
+    (738, "Vulnerability"),
+    (740, "Might"),
+    (742, "Weakness"),
+    (743, "Aegis"),
+    (762, "Determined"),
+    (770, "Downed"),
+    (791, "Fear"),
+    (833, "Daze"),
+    (861, "Confusion"),
+    (872, "Stun"),
+    (873, "Resolution"),
+    (890, "Revealed"),
+    (1066, "Resurrect"),
+    (1122, "Stability"),
+    (1175, "Bandage"),
+    (1187, "Quickness"),
+    (5974, "Superspeed"),
+    (13017, "Stealth"),
+    (19426, "Torment"),
+    (26766, "Slow"),
+    (26980, "Resistance"),
+    (27705, "Taunt"),
+    (30328, "Alacrity"),
+    (65001, "Dodge"),
+];
+
+/// The name of a well-known skill or buff that does not depend on the log, like boons,
+/// conditions, dodging or resurrecting.
+pub fn builtin_name(id: u32) -> Option<&'static str> {
+    BUILTIN_NAMES
+        .binary_search_by_key(&id, |&(id, _)| id)
+        .ok()
+        .map(|i| BUILTIN_NAMES[i].1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BUILTIN_NAMES;
+
+    #[test]
+    fn builtin_names_are_sorted() {
+        assert!(BUILTIN_NAMES.is_sorted_by_key(|&(id, _)| id));
+        assert!(BUILTIN_NAMES.windows(2).all(|w| w[0].0 != w[1].0));
+    }
+}
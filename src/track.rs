@@ -0,0 +1,196 @@
+//! Time-ordered tracks of agent state, decoded from statechanges.
+use std::collections::HashMap;
+
+use crate::event::Event;
+use crate::evtc::{BreakbarState, CbtStateChange, Encounter};
+
+/// Values that can be linearly interpolated between two samples.
+pub trait Interpolate: Copy {
+    /// Interpolates between `self` at `t = 0` and `other` at `t = 1`.
+    fn lerp(self, other: Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl<const N: usize> Interpolate for [f32; N] {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        std::array::from_fn(|i| self[i].lerp(other[i], t))
+    }
+}
+
+/// Samples of a value over time, ordered by event `time`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track<T> {
+    samples: Vec<(u64, T)>,
+}
+
+impl<T> Default for Track<T> {
+    fn default() -> Self {
+        Self {
+            samples: Vec::new(),
+        }
+    }
+}
+
+impl<T> Track<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a sample, keeping the track ordered if it arrives out of order.
+    pub fn push(&mut self, time: u64, value: T) {
+        let index = self.samples.partition_point(|&(t, _)| t <= time);
+        self.samples.insert(index, (time, value));
+    }
+
+    /// The samples as `(time, value)` pairs, in time order.
+    pub fn samples(&self) -> &[(u64, T)] {
+        &self.samples
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, (u64, T)> {
+        self.samples.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// The last sample at or before `time`, `None` if the track starts later.
+    pub fn at(&self, time: u64) -> Option<&T> {
+        let index = self.samples.partition_point(|&(t, _)| t <= time);
+        index.checked_sub(1).map(|i| &self.samples[i].1)
+    }
+}
+
+impl<T: Interpolate> Track<T> {
+    /// The value at `time`, linearly interpolated between the surrounding samples.
+    ///
+    /// Returns `None` before the first sample and the last value after the last sample.
+    pub fn interpolate(&self, time: u64) -> Option<T> {
+        let index = self.samples.partition_point(|&(t, _)| t <= time);
+        let &(before_time, before) = self.samples.get(index.checked_sub(1)?)?;
+        let Some(&(after_time, after)) = self.samples.get(index) else {
+            return Some(before);
+        };
+        let t = (time - before_time) as f32 / (after_time - before_time) as f32;
+        Some(before.lerp(after, t))
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Track<T> {
+    type Item = &'a (u64, T);
+    type IntoIter = std::slice::Iter<'a, (u64, T)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Movement of a single agent, from the `Position`, `Velocity` and `Facing` statechanges.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Movement {
+    pub position: Track<[f32; 3]>,
+    pub velocity: Track<[f32; 3]>,
+    /// Facing direction as a 2D vector
+    pub facing: Track<[f32; 2]>,
+}
+
+impl Encounter {
+    /// The movement tracks of every agent with movement statechanges, keyed by address.
+    pub fn movement(&self) -> HashMap<u64, Movement> {
+        let mut movement: HashMap<u64, Movement> = HashMap::new();
+        let movement_events = self.combat_log.iter().filter(|evt| {
+            matches!(
+                evt.statechange(),
+                CbtStateChange::Position | CbtStateChange::Velocity | CbtStateChange::Facing
+            )
+        });
+        for evt in movement_events {
+            match evt.decode() {
+                Event::Position { agent, position } => {
+                    let track = &mut movement.entry(agent).or_default().position;
+                    track.push(evt.time, position);
+                }
+                Event::Velocity { agent, velocity } => {
+                    let track = &mut movement.entry(agent).or_default().velocity;
+                    track.push(evt.time, velocity);
+                }
+                Event::Facing { agent, facing } => {
+                    let track = &mut movement.entry(agent).or_default().facing;
+                    track.push(evt.time, facing);
+                }
+                _ => {}
+            }
+        }
+        movement
+    }
+
+    /// Health of an agent in percent, 0 to 100, from `HealthPctUpdate`.
+    pub fn health_timeline(&self, agent: u64) -> Track<f32> {
+        self.timeline(CbtStateChange::HealthPctUpdate, agent, |evt| match evt {
+            Event::HealthPctUpdate { health, .. } => Some(health),
+            _ => None,
+        })
+    }
+
+    /// Barrier of an agent in percent of its max health, from `BarrierPctUpdate`.
+    pub fn barrier_timeline(&self, agent: u64) -> Track<f32> {
+        self.timeline(CbtStateChange::BarrierPctUpdate, agent, |evt| match evt {
+            Event::BarrierPctUpdate { barrier, .. } => Some(barrier),
+            _ => None,
+        })
+    }
+
+    /// Defiance bar of an agent in percent, from `BreakbarPercent`.
+    pub fn breakbar_timeline(&self, agent: u64) -> Track<f32> {
+        self.timeline(CbtStateChange::BreakbarPercent, agent, |evt| match evt {
+            Event::BreakbarPercent { breakbar, .. } => Some(breakbar),
+            _ => None,
+        })
+    }
+
+    /// Defiance bar state of an agent, from `BreakbarState`.
+    pub fn breakbar_state_timeline(&self, agent: u64) -> Track<BreakbarState> {
+        self.timeline(CbtStateChange::BreakbarState, agent, |evt| match evt {
+            Event::BreakbarState { state, .. } => Some(state),
+            _ => None,
+        })
+    }
+
+    /// Max health of an agent, from `MaxHealthUpdate`.
+    pub fn max_health_timeline(&self, agent: u64) -> Track<u64> {
+        self.timeline(CbtStateChange::MaxHealthUpdate, agent, |evt| match evt {
+            Event::MaxHealthUpdate { max_health, .. } => Some(max_health),
+            _ => None,
+        })
+    }
+
+    /// Collects the values `sample` extracts from the `kind` statechanges of `agent`.
+    fn timeline<T>(
+        &self,
+        kind: CbtStateChange,
+        agent: u64,
+        sample: impl Fn(Event) -> Option<T>,
+    ) -> Track<T> {
+        let mut track = Track::new();
+        let events = self
+            .combat_log
+            .iter()
+            .filter(|evt| evt.statechange() == kind && evt.src_agent == agent);
+        for evt in events {
+            if let Some(value) = sample(evt.decode()) {
+                track.push(evt.time, value);
+            }
+        }
+        track
+    }
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use common::{build_log, gadget, npc, player, statechange, zeroed};
+use revtc::bossdata::{EliteSpec, Profession};
+use revtc::evtc::{AgentKind, CbtEvent};
+
+fn hit(time: u64, src: (u64, u16), dst: (u64, u16)) -> CbtEvent {
+    let mut evt = zeroed();
+    evt.time = time;
+    (evt.src_agent, evt.src_instid) = src;
+    (evt.dst_agent, evt.dst_instid) = dst;
+    evt.value = 100;
+    evt
+}
+
+#[test]
+fn reused_instance_ids_resolve_by_time() {
+    let log = build_log(
+        15438,
+        &[
+            player(100, b"Char\0:Acc.1234\x001\0"),
+            npc(300, 15438, b"First\0"),
+            npc(301, 15438, b"Second\0"),
+            npc(302, 15438, b"Never seen\0"),
+        ],
+        &[],
+        &[
+            hit(1000, (100, 1), (300, 7)),
+            hit(1200, (100, 1), (300, 7)),
+            hit(2000, (100, 1), (301, 7)),
+            hit(2500, (100, 1), (301, 7)),
+        ],
+    );
+    let encounter = revtc::from_slice(&log).unwrap();
+
+    assert_eq!(encounter.agents[1].aware, Some((1000, 1200)));
+    assert_eq!(encounter.agents[2].aware, Some((2000, 2500)));
+    assert_eq!(encounter.agent_for(7u16, 1100).unwrap().addr, 300);
+    assert_eq!(encounter.agent_for(7u16, 2200).unwrap().addr, 301);
+    assert!(encounter.agent_for(7u16, 1500).is_none());
+
+    let unseen = &encounter.agents[3];
+    assert_eq!((unseen.instid, unseen.aware), (0, None));
+    assert!(!unseen.is_aware_at(1100));
+    assert!(encounter.agent_for(0u16, 1100).is_none());
+    assert_eq!(
+        encounter.agent_for(302u64, 1100).unwrap().name,
+        "Never seen"
+    );
+}
+
+fn minion_hit(time: u64, src: (u64, u16), master_instid: u16, dst: (u64, u16)) -> CbtEvent {
+    let mut evt = hit(time, src, dst);
+    evt.src_master_instid = master_instid;
+    evt
+}
+
+#[test]
+fn master_chains_resolve_to_the_player() {
+    let log = build_log(
+        15438,
+        &[
+            player(100, b"Char\0:Acc.1234\x001\0"),
+            player(101, b"Other\0:Acc.5678\x001\0"),
+            npc(200, 15438, b"Vale Guardian\0"),
+            npc(400, 6648, b"Minion\0"),
+            npc(401, 6648, b"Clone of minion\0"),
+            npc(402, 6648, b"Other minion\0"),
+        ],
+        &[],
+        &[
+            hit(1000, (100, 1), (200, 5)),
+            hit(1000, (101, 2), (200, 5)),
+            minion_hit(1100, (400, 8), 1, (200, 5)),
+            minion_hit(1200, (401, 9), 8, (200, 5)),
+            minion_hit(1250, (400, 8), 1, (200, 5)),
+            minion_hit(1300, (402, 10), 2, (200, 5)),
+            hit(2000, (100, 1), (200, 5)),
+            hit(2000, (101, 2), (200, 5)),
+        ],
+    );
+    let encounter = revtc::from_slice(&log).unwrap();
+    let agent = |addr: u64| encounter.agent_for(addr, 0).unwrap();
+
+    assert_eq!(agent(400).master(), Some(100));
+    assert_eq!(agent(401).master(), Some(400));
+    assert_eq!(encounter.master_of(agent(401)).unwrap().addr, 400);
+    assert_eq!(encounter.root_master(agent(401)).addr, 100);
+    assert_eq!(encounter.root_master(agent(100)).addr, 100);
+    assert!(encounter.master_of(agent(200)).is_none());
+
+    let minions: Vec<u64> = encounter.minions_of(agent(100)).map(|a| a.addr).collect();
+    assert_eq!(minions, [400, 401]);
+    let minions: Vec<u64> = encounter.minions_of(agent(400)).map(|a| a.addr).collect();
+    assert_eq!(minions, [401]);
+}
+
+#[test]
+fn boss_agents_cover_every_target_of_the_encounter() {
+    let log = build_log(
+        21105,
+        &[
+            player(100, b"Char\0:Acc.1234\x001\0"),
+            npc(200, 21105, b"Nikare\0"),
+            npc(201, 21089, b"Kenut\0"),
+            npc(202, 15438, b"Vale Guardian\0"),
+        ],
+        &[],
+        &[],
+    );
+    let encounter = revtc::from_slice(&log).unwrap();
+    let bosses: Vec<u64> = encounter.boss_agents().iter().map(|a| a.addr).collect();
+    assert_eq!(bosses, [200, 201]);
+}
+
+#[test]
+fn boss_agents_follow_log_npc_update() {
+    let mut update = statechange(1000, 22492, 47);
+    update.dst_agent = 203;
+    let log = build_log(
+        15438,
+        &[
+            player(100, b"Char\0:Acc.1234\x001\0"),
+            npc(200, 15438, b"Vale Guardian\0"),
+            npc(201, 22436, b"Construct\0"),
+            npc(202, 21105, b"Nikare\0"),
+            npc(203, 6648, b"Named by the update\0"),
+        ],
+        &[],
+        &[update],
+    );
+    let encounter = revtc::from_slice(&log).unwrap();
+    let bosses: Vec<u64> = encounter.boss_agents().iter().map(|a| a.addr).collect();
+    // The update switches to Fraenir, whose encounter includes the construct
+    assert_eq!(bosses, [200, 201, 203]);
+}
+
+#[test]
+fn agent_kinds_are_decoded_from_prof() {
+    let log = build_log(
+        15438,
+        &[
+            player(100, b"Char\0:Acc.1234\x001\0"),
+            player(101, b"Ch\xFFr\0:Acc.5678\0\x002\0"),
+            npc(200, 15438, b"Vale Guardian\0"),
+            gadget(300, 15456, b"Seeker\0"),
+        ],
+        &[],
+        &[],
+    );
+    let encounter = revtc::from_slice(&log).unwrap();
+    let [first, second, boss, seeker] = &encounter.agents[..] else {
+        panic!("expected four agents");
+    };
+
+    let player = first.player().unwrap();
+    assert_eq!(player.prof, Profession::Guardian);
+    assert_eq!(player.elite_spec, EliteSpec::Firebrand);
+
+    // Invalid UTF-8 is replaced instead of dropping the player
+    assert_eq!(second.name, "Ch\u{FFFD}r");
+    assert_eq!(second.player().unwrap().account_name, "Acc.5678");
+    assert_eq!(encounter.players().count(), 2);
+
+    assert_eq!(boss.kind, AgentKind::Npc { species_id: 15438 });
+    assert_eq!(seeker.kind, AgentKind::Gadget { id: 15456 });
+    assert_eq!(seeker.name, "Seeker");
+    assert_eq!(seeker.species_id(), None);
+    assert!(!seeker.is_player());
+}
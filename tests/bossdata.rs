@@ -0,0 +1,100 @@
+use revtc::bossdata::{BossId, Encounter, Expansion, Fractal, GameMode};
+
+#[test]
+fn grouped_boss_ids_share_an_encounter() {
+    use BossId as BI;
+    use Encounter as E;
+    let groups: &[(&[BossId], Encounter)] = &[
+        (&[BI::Nikare, BI::Kenut], E::TwinLargos),
+        (&[BI::Berg, BI::Zane, BI::Nurella], E::BanditTrio),
+        (
+            &[BI::CaptainMaiTrin, BI::CaptainMaiTrin2, BI::CaptainMaiTrin3],
+            E::MaiTrin,
+        ),
+        (&[BI::TheVoice, BI::TheClaw], E::VoiceAndClaw),
+        (&[BI::Fraenir, BI::FraenirConstruct], E::Fraenir),
+        (
+            &[BI::CerusLonelyTower, BI::DeimosLonelyTower],
+            E::CerusAndDeimos,
+        ),
+    ];
+    for &(bosses, encounter) in groups {
+        for &boss in bosses {
+            assert_eq!(boss.encounter(), Some(encounter), "{boss:?}");
+        }
+        assert_eq!(encounter.bosses(), bosses);
+    }
+    for boss in [BI::Wvw, BI::Instance, BI::Unknown] {
+        assert_eq!(boss.encounter(), None);
+    }
+}
+
+#[test]
+fn encounter_categories() {
+    use BossId as BI;
+    use Expansion as X;
+    let table = [
+        (
+            BI::ValeGuardian,
+            GameMode::Raid,
+            Some(1),
+            None,
+            X::HeartOfThorns,
+        ),
+        (BI::Dhuum, GameMode::Raid, Some(5), None, X::HeartOfThorns),
+        (BI::Qadim, GameMode::Raid, Some(6), None, X::PathOfFire),
+        (BI::Ura, GameMode::Raid, Some(8), None, X::JanthirWilds),
+        (
+            BI::Mama,
+            GameMode::Fractal,
+            None,
+            Some(Fractal::Nightmare),
+            X::Core,
+        ),
+        (
+            BI::EparchLonelyTower,
+            GameMode::Fractal,
+            None,
+            Some(Fractal::LonelyTower),
+            X::Core,
+        ),
+        (
+            BI::Boneskinner,
+            GameMode::Strike,
+            None,
+            None,
+            X::IcebroodSaga,
+        ),
+        (BI::Freezie, GameMode::Strike, None, None, X::Core),
+        (BI::Ankka, GameMode::Strike, None, None, X::EndOfDragons),
+        (BI::PrototypeIndigoCm, GameMode::Strike, None, None, X::Core),
+        (
+            BI::Cerus,
+            GameMode::Strike,
+            None,
+            None,
+            X::SecretsOfTheObscure,
+        ),
+        (BI::VitalGolem, GameMode::Golem, None, None, X::Core),
+    ];
+    for (boss, game_mode, wing, fractal, expansion) in table {
+        let encounter = boss.encounter().unwrap();
+        assert_eq!(boss.game_mode(), Some(game_mode), "{boss:?}");
+        assert_eq!(encounter.game_mode(), game_mode, "{boss:?}");
+        assert_eq!(encounter.wing(), wing, "{boss:?}");
+        assert_eq!(encounter.fractal(), fractal, "{boss:?}");
+        assert_eq!(encounter.expansion(), expansion, "{boss:?}");
+    }
+    assert_eq!(BossId::Wvw.game_mode(), Some(GameMode::WvW));
+    assert_eq!(BossId::Instance.game_mode(), None);
+}
+
+#[test]
+fn every_boss_is_listed_by_its_encounter() {
+    for id in 0..=u16::MAX {
+        let boss = BossId::from_header_id(id);
+        if let Some(encounter) = boss.encounter() {
+            assert!(encounter.bosses().contains(&boss), "{boss:?}");
+        }
+    }
+}
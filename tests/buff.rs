@@ -0,0 +1,69 @@
+use revtc::buff::{BuffCategory, StackingType};
+use revtc::event::Event;
+use revtc::evtc::CbtEvent;
+
+/// A buffer where every byte holds its own index, so misplaced fields are easy to spot.
+fn indexed<const N: usize>() -> [u8; N] {
+    std::array::from_fn(|i| i as u8)
+}
+
+#[test]
+fn buff_info_fields() {
+    let mut bytes = indexed::<{ CbtEvent::SIZE }>();
+    bytes[56] = 30;
+    bytes[59] = 2;
+    bytes[60] = 2;
+    let Event::BuffInfo { skill_id, info } = CbtEvent::from_bytes(&bytes).decode() else {
+        panic!("not a BuffInfo event");
+    };
+    assert_eq!(skill_id, 0x27262524);
+    assert_eq!(info.stacking, StackingType::CappedDuration);
+    assert_eq!(info.category, BuffCategory::Condition);
+    assert_eq!(info.max_stacks, 0x2D2C);
+    assert_eq!(info.duration_cap, 0x23222120);
+    assert!(info.invulnerable && info.invert && info.resistance);
+
+    for (raw, stacking) in [
+        (0, StackingType::StackingConditionalLoss),
+        (3, StackingType::Regeneration),
+        (5, StackingType::Force),
+    ] {
+        bytes[60] = raw;
+        let Event::BuffInfo { info, .. } = CbtEvent::from_bytes(&bytes).decode() else {
+            panic!("not a BuffInfo event");
+        };
+        assert_eq!(info.stacking, stacking);
+        assert_eq!(stacking.as_u8(), raw);
+    }
+}
+
+#[test]
+fn buff_formula_fields() {
+    // Floats 1 to 9 from time on, skillid, then floats 10 and 11 over the instance IDs
+    let mut bytes = [0; CbtEvent::SIZE];
+    let offsets = [0, 4, 8, 12, 16, 20, 24, 28, 32, 40, 44];
+    for (i, offset) in offsets.into_iter().enumerate() {
+        bytes[offset..offset + 4].copy_from_slice(&(i as f32 + 1.0).to_le_bytes());
+    }
+    bytes[36..40].copy_from_slice(&740u32.to_le_bytes());
+    bytes[56] = 31;
+    let Event::BuffFormula { skill_id, formula } = CbtEvent::from_bytes(&bytes).decode() else {
+        panic!("not a BuffFormula event");
+    };
+    assert_eq!(skill_id, 740);
+    assert_eq!(
+        [formula.kind, formula.attribute1, formula.attribute2],
+        [1.0, 2.0, 3.0]
+    );
+    assert_eq!(formula.parameters, [4.0, 5.0, 6.0]);
+    assert_eq!(
+        [
+            formula.trait_condition_source,
+            formula.trait_condition_self,
+            formula.content_reference,
+            formula.buff_condition_source,
+            formula.buff_condition_self
+        ],
+        [7.0, 8.0, 9.0, 10.0, 11.0]
+    );
+}
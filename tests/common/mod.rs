@@ -0,0 +1,133 @@
+//! Builders for synthetic evtc logs.
+#![allow(dead_code)]
+
+use std::io::Write;
+
+use revtc::evtc::CbtEvent;
+
+pub fn agent(addr: u64, prof: u32, is_elite: u32, name: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.extend(addr.to_le_bytes());
+    buf.extend(prof.to_le_bytes());
+    buf.extend(is_elite.to_le_bytes());
+    buf.extend([0; 12]);
+    let mut padded = [0; 64];
+    padded[..name.len()].copy_from_slice(name);
+    buf.extend(padded);
+    buf.extend([0; 4]);
+    buf
+}
+
+pub fn player(addr: u64, name: &[u8]) -> Vec<u8> {
+    agent(addr, 1, 62, name)
+}
+
+pub fn npc(addr: u64, species_id: u16, name: &[u8]) -> Vec<u8> {
+    agent(addr, species_id.into(), 0xFFFFFFFF, name)
+}
+
+/// Gadgets have `0xFFFF` in the upper half of `prof` and their ID in the lower half.
+pub fn gadget(addr: u64, id: u16, name: &[u8]) -> Vec<u8> {
+    agent(addr, 0xFFFF0000 | u32::from(id), 0xFFFFFFFF, name)
+}
+
+pub fn skill(id: i32, name: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.extend(id.to_le_bytes());
+    let mut padded = [0; 64];
+    padded[..name.len()].copy_from_slice(name);
+    buf.extend(padded);
+    buf
+}
+
+/// An event with every field zeroed, to be filled in by the test.
+pub fn zeroed() -> CbtEvent {
+    CbtEvent::from_bytes(&[0; CbtEvent::SIZE])
+}
+
+pub fn statechange(time: u64, src_agent: u64, is_statechange: u8) -> CbtEvent {
+    let mut evt = zeroed();
+    evt.time = time;
+    evt.src_agent = src_agent;
+    evt.is_statechange = is_statechange;
+    evt
+}
+
+/// Serializes an event in the revision 1 layout.
+pub fn event_bytes(evt: &CbtEvent) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(CbtEvent::SIZE);
+    buf.extend(evt.time.to_le_bytes());
+    buf.extend(evt.src_agent.to_le_bytes());
+    buf.extend(evt.dst_agent.to_le_bytes());
+    buf.extend(evt.value.to_le_bytes());
+    buf.extend(evt.buff_dmg.to_le_bytes());
+    buf.extend(evt.overstack_value.to_le_bytes());
+    buf.extend(evt.skillid.to_le_bytes());
+    buf.extend(evt.src_instid.to_le_bytes());
+    buf.extend(evt.dst_instid.to_le_bytes());
+    buf.extend(evt.src_master_instid.to_le_bytes());
+    buf.extend(evt.dst_master_instid.to_le_bytes());
+    buf.extend([
+        evt.iff,
+        evt.buff,
+        evt.result,
+        evt.is_activation,
+        evt.is_buffremove,
+        evt.is_ninety,
+        evt.is_fifty,
+        evt.is_moving,
+        evt.is_statechange,
+        evt.is_flanking,
+        evt.is_shields,
+        evt.is_offcycle,
+        evt.pad61,
+        evt.pad62,
+        evt.pad63,
+        evt.pad64,
+    ]);
+    buf
+}
+
+/// A revision 1 log from arcdps build 2024-10-30.
+pub fn build_log(
+    boss_id: u16,
+    agents: &[Vec<u8>],
+    skills: &[Vec<u8>],
+    events: &[CbtEvent],
+) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.extend(b"EVTC20241030\x01");
+    buf.extend(boss_id.to_le_bytes());
+    buf.push(0);
+    buf.extend((agents.len() as u32).to_le_bytes());
+    agents.iter().for_each(|a| buf.extend(a));
+    buf.extend((skills.len() as u32).to_le_bytes());
+    skills.iter().for_each(|s| buf.extend(s));
+    events.iter().for_each(|e| buf.extend(event_bytes(e)));
+    buf
+}
+
+/// Vale Guardian log with a player pov and one event after it.
+pub fn sample_log() -> Vec<u8> {
+    build_log(
+        15438,
+        &[
+            player(100, b"Char\0:Acc.1234\x001\0"),
+            npc(200, 15438, b"Vale Guardian\0"),
+        ],
+        &[skill(5, b"")],
+        &[statechange(1000, 100, 13), statechange(1100, 100, 0)],
+    )
+}
+
+/// Wraps `data` in a zip archive, preceded by the given directory entries.
+pub fn zip(directories: &[&str], data: &[u8]) -> Vec<u8> {
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default();
+    for dir in directories {
+        zip.add_directory(*dir, options).unwrap();
+    }
+    zip.start_file("log.evtc", options).unwrap();
+    zip.write_all(data).unwrap();
+    zip.finish().unwrap().into_inner()
+}
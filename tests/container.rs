@@ -0,0 +1,36 @@
+mod common;
+
+use common::{sample_log, zip};
+
+#[test]
+fn reads_zipped_log() {
+    let encounter = revtc::from_slice(&zip(&[], &sample_log())).unwrap();
+    assert_eq!(encounter.header.boss_id, 15438);
+    assert_eq!(encounter.agents.len(), 2);
+    assert_eq!(encounter.combat_log.len(), 2);
+}
+
+#[test]
+fn skips_directory_entries() {
+    let data = zip(&["logs/", "logs/Vale Guardian/"], &sample_log());
+    let encounter = revtc::from_slice(&data).unwrap();
+    assert_eq!(encounter.pov.unwrap().addr, 100);
+}
+
+#[test]
+fn archive_without_files_is_empty() {
+    let mut archive = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    archive
+        .add_directory("logs/", zip::write::SimpleFileOptions::default())
+        .unwrap();
+    let data = archive.finish().unwrap().into_inner();
+    let err = revtc::from_slice(&data).unwrap_err();
+    assert!(matches!(err, revtc::Error::EmptyArchive));
+}
+
+#[test]
+fn rejects_unknown_format() {
+    let err = revtc::from_slice(b"not a log at all").unwrap_err();
+    assert!(matches!(err, revtc::Error::UnknownFormat));
+    assert_eq!(err.section(), None);
+}
@@ -0,0 +1,285 @@
+mod common;
+
+use common::sample_log as log;
+use revtc::event::Event;
+use revtc::evtc::{CbtEvent, CbtResult, EvtcAgent, EvtcSkill};
+
+/// A buffer where every byte holds its own index, so misplaced fields are easy to spot.
+fn indexed<const N: usize>() -> [u8; N] {
+    std::array::from_fn(|i| i as u8)
+}
+
+#[test]
+fn event_fields_are_little_endian() {
+    let evt = CbtEvent::from_bytes(&indexed());
+    assert_eq!(evt.time, 0x0706050403020100);
+    assert_eq!(evt.src_agent, 0x0F0E0D0C0B0A0908);
+    assert_eq!(evt.dst_agent, 0x1716151413121110);
+    assert_eq!(evt.value, 0x1B1A1918);
+    assert_eq!(evt.buff_dmg, 0x1F1E1D1C);
+    assert_eq!(evt.overstack_value, 0x23222120);
+    assert_eq!(evt.skillid, 0x27262524);
+    assert_eq!(evt.src_instid, 0x2928);
+    assert_eq!(evt.dst_instid, 0x2B2A);
+    assert_eq!(evt.src_master_instid, 0x2D2C);
+    assert_eq!(evt.dst_master_instid, 0x2F2E);
+    assert_eq!(
+        [
+            evt.iff,
+            evt.buff,
+            evt.result,
+            evt.is_activation,
+            evt.is_buffremove
+        ],
+        [0x30, 0x31, 0x32, 0x33, 0x34]
+    );
+    assert_eq!(
+        [
+            evt.is_ninety,
+            evt.is_fifty,
+            evt.is_moving,
+            evt.is_statechange
+        ],
+        [0x35, 0x36, 0x37, 0x38]
+    );
+    assert_eq!(
+        [evt.is_flanking, evt.is_shields, evt.is_offcycle],
+        [0x39, 0x3A, 0x3B]
+    );
+    assert_eq!(
+        [evt.pad61, evt.pad62, evt.pad63, evt.pad64],
+        [0x3C, 0x3D, 0x3E, 0x3F]
+    );
+}
+
+#[test]
+fn revision_0_event_is_normalized() {
+    let evt = CbtEvent::from_bytes_rev0(&indexed());
+    assert_eq!(evt.time, 0x0706050403020100);
+    assert_eq!(evt.value, 0x1B1A1918);
+    assert_eq!(evt.buff_dmg, 0x1F1E1D1C);
+    assert_eq!(evt.overstack_value, 0x2120);
+    assert_eq!(evt.skillid, 0x2322);
+    assert_eq!(evt.src_instid, 0x2524);
+    assert_eq!(evt.dst_instid, 0x2726);
+    assert_eq!(evt.src_master_instid, 0x2928);
+    assert_eq!(evt.dst_master_instid, 0);
+    assert_eq!([evt.iff, evt.buff, evt.result], [0x33, 0x34, 0x35]);
+    assert_eq!([evt.is_activation, evt.is_buffremove], [0x36, 0x37]);
+    assert_eq!(evt.is_statechange, 0x3B);
+    assert_eq!(evt.is_offcycle, 0x3E);
+}
+
+#[test]
+fn agent_fields_are_little_endian() {
+    let agent = EvtcAgent::from_bytes(&indexed());
+    assert_eq!(agent.addr(), 0x0706050403020100);
+    assert_eq!(agent.prof(), 0x0B0A0908);
+    assert_eq!(agent.is_elite(), 0x0F0E0D0C);
+    assert_eq!(agent.toughness(), 0x1110);
+    assert_eq!(agent.concentration(), 0x1312);
+    assert_eq!(agent.healing(), 0x1514);
+    assert_eq!(agent.hitbox_width(), 0x1716);
+    assert_eq!(agent.condition(), 0x1918);
+    assert_eq!(agent.hitbox_height(), 0x1B1A);
+    assert_eq!(agent.name()[0], 0x1C);
+    assert_eq!(agent.name()[63], 0x5B);
+}
+
+#[test]
+fn skill_fields_are_little_endian() {
+    let skill = EvtcSkill::from_bytes(&indexed());
+    assert_eq!(skill.id(), 0x03020100);
+    assert_eq!(skill.name()[0], 0x04);
+    assert_eq!(skill.name()[63], 0x43);
+}
+
+#[test]
+fn huge_table_counts_are_truncated() {
+    let header = &log()[..16];
+    let mut log = header.to_vec();
+    log.extend(u32::MAX.to_le_bytes());
+    let err = revtc::from_slice(&log).unwrap_err();
+    assert!(matches!(
+        err,
+        revtc::Error::Truncated {
+            section: revtc::Section::AgentTable,
+            offset: 20
+        }
+    ));
+
+    let mut log = header.to_vec();
+    log.extend(0u32.to_le_bytes());
+    log.extend(u32::MAX.to_le_bytes());
+    log.extend([0; 10]);
+    let err = revtc::from_slice(&log).unwrap_err();
+    assert!(matches!(
+        err,
+        revtc::Error::Truncated {
+            section: revtc::Section::SkillTable,
+            offset: 24
+        }
+    ));
+}
+
+#[test]
+fn parses_log() {
+    let encounter = revtc::from_slice(&log()).unwrap();
+    assert_eq!(
+        encounter.header.version,
+        revtc::evtc::ArcVersion::new(2024, 10, 30)
+    );
+    assert_eq!(encounter.header.revision, 1);
+    assert_eq!(encounter.header.boss_id, 15438);
+    assert_eq!(encounter.agents.len(), 2);
+    assert_eq!(encounter.agents[0].name, "Char");
+    let player = encounter.agents[0].player().unwrap();
+    assert_eq!(player.account_name, "Acc.1234");
+    assert_eq!(player.subgroup, "1");
+    assert_eq!(encounter.agents[1].name, "Vale Guardian");
+    assert_eq!(encounter.agents[1].species_id(), Some(15438));
+    assert_eq!(encounter.skills.len(), 1);
+    assert_eq!(encounter.combat_log.len(), 2);
+    assert_eq!(encounter.combat_log[1].time, 1100);
+    let bosses: Vec<u64> = encounter.boss_agents().iter().map(|a| a.addr).collect();
+    assert_eq!(bosses, [200]);
+    assert_eq!(encounter.pov.unwrap().addr, 100);
+}
+
+#[test]
+fn truncated_log_reports_offset() {
+    let log = log();
+    let err = revtc::from_slice(&log[..log.len() - 10]).unwrap_err();
+    assert_eq!(err.section(), Some(revtc::Section::CombatLog));
+    assert_eq!(err.offset(), Some(log.len() as u64 - 64));
+}
+
+#[test]
+fn invalid_version_is_rejected() {
+    let mut log = log();
+    log[4..12].copy_from_slice(b"2024\xFF030");
+    let err = revtc::from_slice(&log).unwrap_err();
+    assert!(matches!(err, revtc::Error::InvalidVersion { .. }));
+    assert_eq!(err.offset(), Some(4));
+}
+
+/// An event with every flag set that could make it look like a different kind of event.
+fn ambiguous() -> CbtEvent {
+    let mut evt = common::zeroed();
+    evt.src_agent = 100;
+    evt.dst_agent = 200;
+    evt.value = 1234;
+    evt.buff_dmg = 56;
+    evt.skillid = 740;
+    evt.is_statechange = 4;
+    evt.is_activation = 1;
+    evt.is_buffremove = 1;
+    evt.buff = 1;
+    evt
+}
+
+#[test]
+fn statechange_takes_precedence() {
+    assert_eq!(ambiguous().decode(), Event::ChangeDead { agent: 100 });
+
+    let mut evt = ambiguous();
+    evt.is_statechange = 250;
+    assert_eq!(evt.decode(), Event::UnknownStateChange(250));
+}
+
+#[test]
+fn activation_comes_before_buff_remove() {
+    let mut evt = ambiguous();
+    evt.is_statechange = 0;
+    assert_eq!(
+        evt.decode(),
+        Event::ActivationStart {
+            agent: 100,
+            skill_id: 740,
+            duration: 1234,
+            scaled_duration: 56,
+        }
+    );
+}
+
+#[test]
+fn buff_remove_comes_before_buff_events() {
+    let mut evt = ambiguous();
+    evt.is_statechange = 0;
+    evt.is_activation = 0;
+    evt.result = 3;
+    assert_eq!(
+        evt.decode(),
+        Event::BuffRemoveAll {
+            src: 100,
+            dst: 200,
+            skill_id: 740,
+            duration: 1234,
+            longest_stack: 56,
+            stacks: 3,
+        }
+    );
+}
+
+#[test]
+fn buff_with_value_is_apply() {
+    let mut evt = ambiguous();
+    evt.is_statechange = 0;
+    evt.is_activation = 0;
+    evt.is_buffremove = 0;
+    evt.overstack_value = 7;
+    evt.pad61 = 9;
+    assert_eq!(
+        evt.decode(),
+        Event::BuffApply {
+            src: 100,
+            dst: 200,
+            skill_id: 740,
+            duration: 1234,
+            overstack: 7,
+            stack_id: 9,
+        }
+    );
+}
+
+#[test]
+fn buff_without_value_is_damage() {
+    let mut evt = ambiguous();
+    evt.is_statechange = 0;
+    evt.is_activation = 0;
+    evt.is_buffremove = 0;
+    evt.value = 0;
+    evt.overstack_value = 7;
+    assert_eq!(
+        evt.decode(),
+        Event::BuffDamage {
+            src: 100,
+            dst: 200,
+            skill_id: 740,
+            damage: 56,
+            shield_damage: 7,
+            result: 0,
+        }
+    );
+}
+
+#[test]
+fn plain_event_is_direct_damage() {
+    let mut evt = ambiguous();
+    evt.is_statechange = 0;
+    evt.is_activation = 0;
+    evt.is_buffremove = 0;
+    evt.buff = 0;
+    evt.result = 1;
+    assert_eq!(
+        evt.decode(),
+        Event::DirectDamage {
+            src: 100,
+            dst: 200,
+            skill_id: 740,
+            damage: 1234,
+            shield_damage: 0,
+            result: CbtResult::Crit,
+        }
+    );
+}
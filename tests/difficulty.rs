@@ -0,0 +1,153 @@
+mod common;
+
+use common::{build_log, npc, player, statechange, zeroed};
+use revtc::bossdata::Difficulty;
+use revtc::evtc::CbtEvent;
+
+const EMBOLDENED: u32 = 68087;
+
+fn max_health(agent: u64, health: u64) -> CbtEvent {
+    let mut evt = statechange(1000, agent, 12);
+    evt.dst_agent = health;
+    evt
+}
+
+fn difficulty(boss_id: u16, events: &[CbtEvent]) -> Difficulty {
+    let log = build_log(
+        boss_id,
+        &[
+            player(100, b"Char\0:Acc.1234\x001\0"),
+            npc(200, boss_id, b"Boss\0"),
+        ],
+        &[],
+        events,
+    );
+    revtc::from_slice(&log).unwrap().difficulty()
+}
+
+#[test]
+fn challenge_mode_species() {
+    assert_eq!(difficulty(24266, &[]), Difficulty::ChallengeMode);
+    assert_eq!(difficulty(24485, &[]), Difficulty::NormalMode);
+}
+
+#[test]
+fn challenge_mode_health() {
+    // Mursaat Overseer has 22,021,440 health in normal mode and about 30 million in challenge mode
+    assert_eq!(
+        difficulty(17172, &[max_health(200, 22_021_440)]),
+        Difficulty::NormalMode
+    );
+    assert_eq!(
+        difficulty(17172, &[max_health(200, 30_021_120)]),
+        Difficulty::ChallengeMode
+    );
+    // Only the boss' max health counts
+    assert_eq!(
+        difficulty(17172, &[max_health(100, 30_021_120)]),
+        Difficulty::Unknown
+    );
+}
+
+fn fractal_scale(scale: u64) -> CbtEvent {
+    statechange(1000, scale, 50)
+}
+
+#[test]
+fn fractal_scale_tells_fractal_challenge_modes() {
+    // Mama in Nightmare, Kanaxai in Silent Surf
+    assert_eq!(
+        difficulty(17021, &[fractal_scale(100)]),
+        Difficulty::ChallengeMode
+    );
+    assert_eq!(
+        difficulty(17021, &[fractal_scale(97)]),
+        Difficulty::NormalMode
+    );
+    assert_eq!(
+        difficulty(25577, &[fractal_scale(97)]),
+        Difficulty::ChallengeMode
+    );
+    assert_eq!(difficulty(17021, &[]), Difficulty::Unknown);
+}
+
+#[test]
+fn undetectable_challenge_modes_are_unknown() {
+    // Greer has a challenge mode that neither species nor health tell apart
+    assert_eq!(difficulty(26725, &[]), Difficulty::Unknown);
+    let events = [statechange(1000, 100, 13), emboldened(1000, 0)];
+    assert_eq!(difficulty(26725, &events), Difficulty::Emboldened(1));
+    // Encounters without a challenge mode
+    assert_eq!(difficulty(15438, &[]), Difficulty::NormalMode);
+    assert_eq!(difficulty(16246, &[]), Difficulty::NormalMode);
+}
+
+#[test]
+fn legendary_mode_health() {
+    assert_eq!(
+        difficulty(25989, &[max_health(200, 100_000_000)]),
+        Difficulty::NormalMode
+    );
+    assert_eq!(
+        difficulty(25989, &[max_health(200, 150_000_000)]),
+        Difficulty::ChallengeMode
+    );
+    assert_eq!(
+        difficulty(25989, &[max_health(200, 250_000_000)]),
+        Difficulty::LegendaryMode
+    );
+}
+
+/// An Emboldened event for the player; removals name the player in `src_agent`.
+fn emboldened(time: u64, is_buffremove: u8) -> CbtEvent {
+    let mut evt = zeroed();
+    evt.time = time;
+    if is_buffremove == 0 {
+        evt.dst_agent = 100;
+    } else {
+        evt.src_agent = 100;
+    }
+    evt.skillid = EMBOLDENED;
+    evt.buff = 1;
+    evt.value = 1000;
+    evt.is_buffremove = is_buffremove;
+    evt
+}
+
+#[test]
+fn emboldened_counts_stacks_held_at_once() {
+    let mut initial = emboldened(1000, 0);
+    initial.is_statechange = 18;
+    let events = [
+        statechange(1000, 100, 13),
+        initial,
+        emboldened(1000, 0),
+        // Reapplied after a single stack expired, still two stacks
+        emboldened(2000, 2),
+        emboldened(2100, 0),
+        // Removed and reapplied, still two stacks
+        emboldened(3000, 1),
+        emboldened(3100, 0),
+        emboldened(3100, 0),
+    ];
+    assert_eq!(difficulty(15438, &events), Difficulty::Emboldened(2));
+}
+
+#[test]
+fn emboldened_only_counts_the_pov() {
+    let mut other = emboldened(1000, 0);
+    other.dst_agent = 200;
+    let events = [statechange(1000, 100, 13), other];
+    assert_eq!(difficulty(15438, &events), Difficulty::NormalMode);
+
+    // The player removing a stack from someone else keeps their own stacks
+    let mut removed_by_player = emboldened(1500, 2);
+    (removed_by_player.src_agent, removed_by_player.dst_agent) = (200, 100);
+    let events = [
+        statechange(1000, 100, 13),
+        emboldened(1000, 0),
+        removed_by_player,
+        emboldened(2000, 0),
+    ];
+    assert_eq!(difficulty(15438, &events), Difficulty::Emboldened(2));
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use common::{build_log, player, statechange};
+use revtc::bossdata::{Language, Ruleset};
+use revtc::evtc::{CbtEvent, EncounterMetadata};
+
+/// The metadata of a log with the given statechanges.
+fn metadata(events: &[CbtEvent]) -> EncounterMetadata {
+    let log = build_log(
+        15438,
+        &[player(100, b"Char\0:Acc.1234\x001\0")],
+        &[],
+        events,
+    );
+    revtc::from_slice(&log).unwrap().metadata
+}
+
+/// An `ArcBuild` statechange, whose build string starts at `src_agent`.
+fn arc_build(build: &str) -> CbtEvent {
+    let mut bytes = [0; CbtEvent::SIZE];
+    bytes[8..8 + build.len()].copy_from_slice(build.as_bytes());
+    bytes[56] = 54;
+    CbtEvent::from_bytes(&bytes)
+}
+
+#[test]
+fn metadata_statechanges() {
+    let metadata = metadata(&[
+        statechange(0, 1155, 25),
+        statechange(0, 150_000, 15),
+        statechange(0, 3, 14),
+        statechange(0, 4321, 16),
+        arc_build("20241030.101010-123-x64"),
+        statechange(0, 2, 52),
+        statechange(0, 100, 50),
+        statechange(0, 1_234_567, 42),
+    ]);
+    assert_eq!(metadata.map_id, Some(1155));
+    assert_eq!(metadata.gw_build, Some(150_000));
+    assert_eq!(metadata.language, Some(Language::German));
+    assert_eq!(metadata.shard_id, Some(4321));
+    assert_eq!(
+        metadata.arc_build.as_deref(),
+        Some("20241030.101010-123-x64")
+    );
+    assert_eq!(metadata.ruleset, Some(Ruleset::WvW));
+    assert_eq!(metadata.fractal_scale, Some(100));
+    assert_eq!(metadata.instance_start, Some(1_234_567));
+}
+
+#[test]
+fn first_value_wins() {
+    let metadata = metadata(&[
+        statechange(0, 1155, 25),
+        statechange(0, 150_000, 15),
+        statechange(0, 3, 14),
+        statechange(0, 4321, 16),
+        arc_build("20241030"),
+        statechange(0, 2, 52),
+        statechange(0, 100, 50),
+        statechange(0, 1_234_567, 42),
+        statechange(10, 1062, 25),
+        statechange(10, 160_000, 15),
+        statechange(10, 0, 14),
+        statechange(10, 1, 16),
+        arc_build("20250101"),
+        statechange(10, 1, 52),
+        statechange(10, 99, 50),
+        statechange(10, 1, 42),
+    ]);
+    assert_eq!(metadata.map_id, Some(1155));
+    assert_eq!(metadata.gw_build, Some(150_000));
+    assert_eq!(metadata.language, Some(Language::German));
+    assert_eq!(metadata.shard_id, Some(4321));
+    assert_eq!(metadata.arc_build.as_deref(), Some("20241030"));
+    assert_eq!(metadata.ruleset, Some(Ruleset::WvW));
+    assert_eq!(metadata.fractal_scale, Some(100));
+    assert_eq!(metadata.instance_start, Some(1_234_567));
+}
+
+#[test]
+fn missing_statechanges_stay_empty() {
+    assert_eq!(metadata(&[]), EncounterMetadata::default());
+}
+
+#[test]
+fn arc_build_spans_every_field_before_is_statechange() {
+    let build = "x".repeat(48);
+    assert_eq!(
+        metadata(&[arc_build(&build)]).arc_build.as_deref(),
+        Some(build.as_str())
+    );
+}
+
+#[test]
+fn ruleset_flags() {
+    assert_eq!(Ruleset::from_evtc(1), Some(Ruleset::PvE));
+    assert_eq!(Ruleset::from_evtc(2), Some(Ruleset::WvW));
+    assert_eq!(Ruleset::from_evtc(4), Some(Ruleset::PvP));
+    // The lowest set bit wins
+    assert_eq!(Ruleset::from_evtc(6), Some(Ruleset::WvW));
+    assert_eq!(Ruleset::from_evtc(0), None);
+    assert_eq!(Language::from_evtc(1), None);
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use common::{build_log, event_bytes, npc, player, sample_log, statechange};
+use revtc::evtc::CbtEvent;
+use revtc::outcome::Outcome;
+
+/// A log of `boss_id` with the given events.
+fn outcome_with(boss_id: u16, npcs: &[(u64, u16)], events: &[CbtEvent]) -> Option<Outcome> {
+    let mut agents = vec![player(100, b"Char\0:Acc.1234\x001\0")];
+    agents.extend(
+        npcs.iter()
+            .map(|&(addr, species)| npc(addr, species, b"Boss\0")),
+    );
+    revtc::from_slice(&build_log(boss_id, &agents, &[], events))
+        .unwrap()
+        .outcome()
+}
+
+/// A log of `boss_id` where the agents at `dead` die.
+fn outcome(boss_id: u16, npcs: &[(u64, u16)], dead: &[u64]) -> Option<Outcome> {
+    let events: Vec<_> = dead
+        .iter()
+        .map(|&addr| statechange(2000, addr, 4))
+        .collect();
+    outcome_with(boss_id, npcs, &events)
+}
+
+fn health(time: u64, agent: u64, percent: u64) -> CbtEvent {
+    let mut evt = statechange(time, agent, 8);
+    evt.dst_agent = percent * 100;
+    evt
+}
+
+#[test]
+fn fraenir_needs_fraenir_dead() {
+    let npcs = [(200, 22492), (201, 22436)];
+    for boss_id in [22492, 22436] {
+        assert_eq!(outcome(boss_id, &npcs, &[]), Some(Outcome::Failure));
+        assert_eq!(outcome(boss_id, &npcs, &[201]), Some(Outcome::Failure));
+        assert_eq!(outcome(boss_id, &npcs, &[200]), Some(Outcome::Success));
+    }
+}
+
+#[test]
+fn cerus_and_deimos_both_have_to_die() {
+    let npcs = [(200, 26257), (201, 26226)];
+    for boss_id in [26257, 26226] {
+        assert_eq!(outcome(boss_id, &npcs, &[200]), Some(Outcome::Failure));
+        assert_eq!(outcome(boss_id, &npcs, &[201]), Some(Outcome::Failure));
+        assert_eq!(outcome(boss_id, &npcs, &[200, 201]), Some(Outcome::Success));
+    }
+}
+
+#[test]
+fn other_targets_dying_is_no_success() {
+    // Vale Guardian with one of its split guardians dying
+    let npcs = [(200, 15438), (201, 15420)];
+    assert_eq!(outcome(15438, &npcs, &[201]), Some(Outcome::Failure));
+    assert_eq!(outcome(15438, &npcs, &[200]), Some(Outcome::Success));
+}
+
+#[test]
+fn bosses_that_never_die_are_killed_at_low_health() {
+    // Dhuum without a reward, e.g. a repeat clear in the same week
+    let dhuum = [(200, 19450)];
+    let events = [health(1000, 200, 50), health(2000, 200, 1)];
+    assert_eq!(outcome_with(19450, &dhuum, &events), Some(Outcome::Success));
+    let events = [health(1000, 200, 50), health(2000, 200, 10)];
+    assert_eq!(outcome_with(19450, &dhuum, &events), Some(Outcome::Failure));
+    let reward = statechange(3000, 0, 17);
+    assert_eq!(
+        outcome_with(19450, &dhuum, &[reward]),
+        Some(Outcome::Success)
+    );
+
+    // Any of the Dragon Void phases
+    let void = [(200, 24375), (201, 43488)];
+    let events = [health(1000, 200, 0), health(2000, 201, 1)];
+    assert_eq!(outcome_with(24375, &void, &events), Some(Outcome::Success));
+}
+
+#[test]
+fn reward_counts_for_every_boss() {
+    let reward = statechange(3000, 0, 17);
+    for boss_id in [17949, 23254, 16247, 15438] {
+        assert_eq!(
+            outcome_with(boss_id, &[(200, boss_id)], &[reward]),
+            Some(Outcome::Success)
+        );
+    }
+}
+
+#[test]
+fn outcome_follows_boss_death() {
+    let mut log = sample_log();
+    assert_eq!(
+        revtc::from_slice(&log).unwrap().outcome(),
+        Some(Outcome::Failure)
+    );
+    log.extend(event_bytes(&statechange(1200, 200, 4)));
+    assert_eq!(
+        revtc::from_slice(&log).unwrap().outcome(),
+        Some(Outcome::Success)
+    );
+}
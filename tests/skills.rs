@@ -0,0 +1,125 @@
+mod common;
+
+use common::{build_log, npc, player, skill, statechange, zeroed};
+use revtc::evtc::CbtEvent;
+use revtc::skill::{builtin_name, SkillInfo, SkillTiming};
+
+fn floats(low: f32, high: f32) -> u64 {
+    u64::from(low.to_bits()) | u64::from(high.to_bits()) << 32
+}
+
+fn skill_info(skill_id: u32, info: [f32; 4]) -> CbtEvent {
+    let mut evt = zeroed();
+    evt.time = floats(info[0], info[1]);
+    evt.src_agent = floats(info[2], info[3]);
+    evt.skillid = skill_id;
+    evt.is_statechange = 32;
+    evt
+}
+
+fn skill_timing(skill_id: u32, timing_type: u64, at: u64) -> CbtEvent {
+    let mut evt = statechange(0, timing_type, 33);
+    evt.dst_agent = at;
+    evt.skillid = skill_id;
+    evt
+}
+
+#[test]
+fn skill_definitions_are_attached_to_their_skills() {
+    let log = build_log(
+        15438,
+        &[
+            player(100, b"Char\0:Acc.1234\x001\0"),
+            npc(200, 15438, b"Vale Guardian\0"),
+        ],
+        &[skill(5, b"Slash\0"), skill(6, b"Unused\0")],
+        &[
+            skill_info(5, [10.0, 130.0, 0.0, 750.0]),
+            skill_timing(5, 1, 250),
+            skill_timing(5, 2, 500),
+            // Only the first SkillInfo counts
+            skill_info(5, [0.0, 0.0, 0.0, 0.0]),
+            skill_timing(9, 1, 100),
+        ],
+    );
+    let encounter = revtc::from_slice(&log).unwrap();
+
+    let slash = encounter.skills.get(5).unwrap();
+    assert_eq!(slash.name.as_deref(), Some("Slash"));
+    assert_eq!(
+        slash.info,
+        Some(SkillInfo {
+            cost: 10.0,
+            range0: 130.0,
+            range1: 0.0,
+            tooltip_time: 750.0,
+        })
+    );
+    assert_eq!(
+        slash.timings,
+        [
+            SkillTiming {
+                timing_type: 1,
+                at: 250
+            },
+            SkillTiming {
+                timing_type: 2,
+                at: 500
+            },
+        ]
+    );
+
+    let unused = encounter.skills.get(6).unwrap();
+    assert_eq!((unused.info, unused.timings.len()), (None, 0));
+
+    // Skills missing from the skill table are added without a name
+    let unlisted = encounter.skills.get(9).unwrap();
+    assert_eq!(unlisted.name, None);
+    assert_eq!(
+        unlisted.timings,
+        [SkillTiming {
+            timing_type: 1,
+            at: 100
+        }]
+    );
+}
+
+#[test]
+fn names_fall_back_to_builtin_names() {
+    let log = build_log(
+        15438,
+        &[player(100, b"Char\0:Acc.1234\x001\0")],
+        &[
+            skill(5, b"Slash\0"),
+            skill(718, b"\0"),
+            skill(740, b"Custom Might\0"),
+            skill(7, b"Bad \xFF name\0"),
+            skill(8, b"\0"),
+        ],
+        &[],
+    );
+    let skills = revtc::from_slice(&log).unwrap().skills;
+
+    assert_eq!(skills.name(5), Some("Slash"));
+    assert_eq!(skills.name(7), Some("Bad \u{FFFD} name"));
+    // Empty names are no names
+    assert_eq!(skills.get(718).unwrap().name, None);
+    assert_eq!(skills.name(718), None);
+    assert_eq!(skills.name_or_builtin(718), Some("Regeneration"));
+    // The name in the log wins over the builtin name
+    assert_eq!(skills.name_or_builtin(740), Some("Custom Might"));
+    // Skills missing from the skill table
+    assert_eq!(skills.name(1187), None);
+    assert_eq!(skills.name_or_builtin(1187), Some("Quickness"));
+    assert_eq!(skills.name_or_builtin(8), None);
+    assert_eq!(skills.name_or_builtin(9), None);
+}
+
+#[test]
+fn builtin_names() {
+    assert_eq!(builtin_name(717), Some("Protection"));
+    assert_eq!(builtin_name(30328), Some("Alacrity"));
+    assert_eq!(builtin_name(65001), Some("Dodge"));
+    assert_eq!(builtin_name(716), None);
+    assert_eq!(builtin_name(u32::MAX), None);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use std::io::{self, Cursor, Read};
+
+use common::{event_bytes, sample_log, statechange, zip};
+use revtc::evtc::EventStream;
+use revtc::{Error, Section};
+
+#[test]
+fn streams_events_of_zipped_log() {
+    let times = revtc::from_reader_streaming(Cursor::new(zip(&[], &sample_log())), |enc| {
+        assert_eq!(enc.agents.len(), 2);
+        enc.events.map(|evt| evt.unwrap().time).collect::<Vec<_>>()
+    })
+    .unwrap();
+    assert_eq!(times, [1000, 1100]);
+}
+
+#[test]
+fn partial_event_ends_stream() {
+    let mut data = event_bytes(&statechange(1000, 100, 13));
+    data.extend(&event_bytes(&statechange(1100, 100, 0))[..10]);
+    let mut events = EventStream::new(Cursor::new(data));
+
+    assert_eq!(events.next().unwrap().unwrap().time, 1000);
+    let err = events.next().unwrap().unwrap_err();
+    assert!(matches!(
+        err,
+        Error::MisalignedLog {
+            offset: 64,
+            trailing: 10
+        }
+    ));
+    assert!(events.next().is_none());
+    assert!(events.next().is_none());
+}
+
+/// Yields one event, then fails.
+struct FailingReader(Cursor<Vec<u8>>);
+
+impl Read for FailingReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.0.read(buf)? {
+            0 => Err(io::Error::other("disk on fire")),
+            n => Ok(n),
+        }
+    }
+}
+
+#[test]
+fn read_error_ends_stream() {
+    let data = event_bytes(&statechange(1000, 100, 13));
+    let mut events = EventStream::new(FailingReader(Cursor::new(data)));
+
+    assert!(events.next().unwrap().is_ok());
+    let err = events.next().unwrap().unwrap_err();
+    assert_eq!(err.section(), Some(Section::CombatLog));
+    assert_eq!(err.offset(), Some(64));
+    assert!(matches!(err, Error::Read { .. }));
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn summary_stops_after_start_metadata() {
+    let mut sqcombatstart = statechange(1000, 0, 9);
+    sqcombatstart.value = 1_700_000_000;
+    let mut events = vec![
+        statechange(1000, 100, 13),
+        statechange(1000, 1062, 25),
+        statechange(1000, 150_000, 15),
+        sqcombatstart,
+    ];
+    events.extend((0..100).map(|i| statechange(1100 + i, 100, 0)));
+    let mut log = common::build_log(
+        15438,
+        &[common::player(100, b"Char\0:Acc.1234\x001\0")],
+        &[],
+        &events,
+    );
+    // A full read would fail on this, so the summary must stop before it
+    log.extend([0; 10]);
+    assert!(revtc::from_slice(&log).is_err());
+
+    let summary = revtc::from_reader_metadata(Cursor::new(&log)).unwrap();
+    assert_eq!(summary.pov.unwrap().addr, 100);
+    assert_eq!(summary.metadata.map_id, Some(1062));
+    assert_eq!(summary.metadata.gw_build, Some(150_000));
+    assert_eq!(
+        summary.metadata.log_start.unwrap().server_time,
+        1_700_000_000
+    );
+    let header_and_tables = log.len() as u64 - 104 * 64 - 10;
+    assert_eq!(summary.evtc_bytes_read, header_and_tables + 4 * 64);
+    assert!(summary.evtc_bytes_read < log.len() as u64);
+
+    // Zipped logs count the same decompressed bytes
+    let zipped = common::zip(&[], &log);
+    let summary = revtc::from_reader_metadata(Cursor::new(&zipped)).unwrap();
+    assert_eq!(summary.evtc_bytes_read, header_and_tables + 4 * 64);
+}
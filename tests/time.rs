@@ -0,0 +1,104 @@
+mod common;
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use common::{build_log, npc, player, statechange};
+use revtc::evtc::{CbtEvent, Encounter, LogBoundary};
+
+/// A `SqCombatStart` or `LogEnd` statechange at `time`.
+fn boundary(time: u64, is_statechange: u8, server_time: i32, local_time: i32) -> CbtEvent {
+    let mut evt = statechange(time, 0, is_statechange);
+    evt.value = server_time;
+    evt.buff_dmg = local_time;
+    evt
+}
+
+/// A log of the player fighting Vale Guardian with the given events.
+fn encounter(events: &[CbtEvent]) -> Encounter {
+    let log = build_log(
+        15438,
+        &[
+            player(100, b"Char\0:Acc.1234\x001\0"),
+            npc(200, 15438, b"Vale Guardian\0"),
+        ],
+        &[],
+        events,
+    );
+    revtc::from_slice(&log).unwrap()
+}
+
+#[test]
+fn boundaries_tie_event_time_to_wall_clock() {
+    let encounter = encounter(&[
+        statechange(900, 100, 13),
+        boundary(1000, 9, 1_700_000_000, 1_700_000_002),
+        statechange(5000, 100, 0),
+        boundary(91_000, 10, 1_700_000_090, 1_700_000_092),
+    ]);
+    let start = encounter.metadata.log_start.unwrap();
+    assert_eq!(
+        start,
+        LogBoundary {
+            time: 1000,
+            server_time: 1_700_000_000,
+            local_time: 1_700_000_002,
+        }
+    );
+    let epoch = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    assert_eq!(start.server_wall_clock(), epoch);
+    assert_eq!(start.local_wall_clock(), epoch + Duration::from_secs(2));
+    assert_eq!(encounter.metadata.log_end.unwrap().time, 91_000);
+
+    assert_eq!(encounter.duration(), Some(90_000));
+    assert_eq!(encounter.relative_ms(5000), Some(4000));
+    assert_eq!(
+        encounter.wall_clock(5000),
+        Some(epoch + Duration::from_millis(4000))
+    );
+}
+
+#[test]
+fn events_before_the_start_are_negative() {
+    let encounter = encounter(&[
+        statechange(900, 100, 13),
+        boundary(1000, 9, 1_700_000_000, 1_700_000_000),
+    ]);
+    let epoch = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    assert_eq!(encounter.relative_ms(900), Some(-100));
+    assert_eq!(
+        encounter.wall_clock(900),
+        Some(epoch - Duration::from_millis(100))
+    );
+}
+
+#[test]
+fn duration_without_log_end_uses_the_last_event() {
+    let encounter = encounter(&[
+        boundary(1000, 9, 1_700_000_000, 1_700_000_000),
+        statechange(4000, 100, 0),
+    ]);
+    assert_eq!(encounter.metadata.log_end, None);
+    assert_eq!(encounter.duration(), Some(3000));
+}
+
+#[test]
+fn last_log_end_counts() {
+    let encounter = encounter(&[
+        boundary(1000, 9, 1_700_000_000, 1_700_000_000),
+        boundary(2000, 10, 1_700_000_001, 1_700_000_001),
+        boundary(3000, 10, 1_700_000_002, 1_700_000_002),
+    ]);
+    assert_eq!(encounter.duration(), Some(2000));
+}
+
+#[test]
+fn no_log_start_means_no_times() {
+    let encounter = encounter(&[
+        statechange(1000, 100, 13),
+        boundary(3000, 10, 1_700_000_002, 1_700_000_002),
+    ]);
+    assert_eq!(encounter.metadata.log_start, None);
+    assert_eq!(encounter.duration(), None);
+    assert_eq!(encounter.relative_ms(1000), None);
+    assert_eq!(encounter.wall_clock(1000), None);
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use common::{build_log, npc, player, sample_log, statechange};
+use revtc::event::Event;
+use revtc::evtc::{BreakbarState, CbtEvent, Encounter};
+use revtc::track::Track;
+
+fn with_dst(mut evt: CbtEvent, dst_agent: u64) -> CbtEvent {
+    evt.dst_agent = dst_agent;
+    evt
+}
+
+fn with_value(mut evt: CbtEvent, value: f32) -> CbtEvent {
+    evt.value = value.to_bits() as i32;
+    evt
+}
+
+/// A log of the player fighting Vale Guardian with the given events.
+fn encounter(events: &[CbtEvent]) -> Encounter {
+    let log = build_log(
+        15438,
+        &[
+            player(100, b"Char\0:Acc.1234\x001\0"),
+            npc(200, 15438, b"Vale Guardian\0"),
+        ],
+        &[],
+        events,
+    );
+    revtc::from_slice(&log).unwrap()
+}
+
+#[test]
+fn health_and_barrier_timelines() {
+    let encounter = encounter(&[
+        with_dst(statechange(1000, 200, 8), 10000),
+        with_dst(statechange(1000, 100, 8), 5000),
+        with_dst(statechange(2000, 200, 8), 9950),
+        with_dst(statechange(1500, 200, 38), 1250),
+    ]);
+    let health = encounter.health_timeline(200);
+    assert_eq!(health.samples(), [(1000, 100.0), (2000, 99.5)]);
+    assert_eq!(encounter.barrier_timeline(200).samples(), [(1500, 12.5)]);
+    assert!(encounter.barrier_timeline(100).is_empty());
+}
+
+#[test]
+fn breakbar_timelines() {
+    let encounter = encounter(&[
+        with_dst(statechange(1000, 200, 34), 0),
+        with_value(statechange(1000, 200, 35), 1.0),
+        with_value(statechange(1500, 200, 35), 0.25),
+        with_dst(statechange(2000, 200, 34), 1),
+        with_value(statechange(2000, 100, 35), 0.5),
+    ]);
+    assert_eq!(
+        encounter.breakbar_timeline(200).samples(),
+        [(1000, 100.0), (1500, 25.0)]
+    );
+    assert_eq!(
+        encounter.breakbar_state_timeline(200).samples(),
+        [
+            (1000, BreakbarState::Active),
+            (2000, BreakbarState::Recover)
+        ]
+    );
+}
+
+#[test]
+fn max_health_timeline() {
+    let encounter = encounter(&[
+        with_dst(statechange(1000, 200, 12), 22_021_440),
+        with_dst(statechange(3000, 200, 12), 30_021_120),
+    ]);
+    let max_health = encounter.max_health_timeline(200);
+    assert_eq!(max_health.at(2000), Some(&22_021_440));
+    assert_eq!(max_health.at(3000), Some(&30_021_120));
+    assert!(revtc::from_slice(&sample_log())
+        .unwrap()
+        .max_health_timeline(200)
+        .is_empty());
+}
+
+#[test]
+fn out_of_order_samples_are_sorted() {
+    let mut track = Track::new();
+    track.push(2000, 2.0);
+    track.push(1000, 1.0);
+    track.push(3000, 3.0);
+    track.push(2000, 2.5);
+    assert_eq!(
+        track.samples(),
+        [(1000, 1.0), (2000, 2.0), (2000, 2.5), (3000, 3.0)]
+    );
+}
+
+#[test]
+fn lookup_before_between_and_after_samples() {
+    let mut track = Track::new();
+    track.push(1000, [0.0, 10.0]);
+    track.push(2000, [10.0, 20.0]);
+
+    assert_eq!(track.at(999), None);
+    assert_eq!(track.at(1500), Some(&[0.0, 10.0]));
+    assert_eq!(track.at(5000), Some(&[10.0, 20.0]));
+
+    assert_eq!(track.interpolate(999), None);
+    assert_eq!(track.interpolate(1000), Some([0.0, 10.0]));
+    assert_eq!(track.interpolate(1250), Some([2.5, 12.5]));
+    assert_eq!(track.interpolate(2000), Some([10.0, 20.0]));
+    assert_eq!(track.interpolate(5000), Some([10.0, 20.0]));
+    assert_eq!(Track::<f32>::new().interpolate(1000), None);
+}
+
+#[test]
+fn position_is_read_from_dst_agent_and_value() {
+    let mut position = statechange(1000, 100, 19);
+    position.dst_agent = u64::from(1.5f32.to_bits()) | u64::from((-2.0f32).to_bits()) << 32;
+    position = with_value(position, 3.25);
+    let encounter = encounter(&[position]);
+    assert_eq!(
+        position.decode(),
+        Event::Position {
+            agent: 100,
+            position: [1.5, -2.0, 3.25]
+        }
+    );
+    let movement = encounter.movement();
+    assert_eq!(
+        movement[&100].position.samples(),
+        [(1000, [1.5, -2.0, 3.25])]
+    );
+    assert!(movement[&100].velocity.is_empty());
+}